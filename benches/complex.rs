@@ -1,4 +1,5 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use stretch::number::OrElse;
 
 fn build_deep_hierarchy(stretch: &mut stretch::node::Stretch) -> stretch::node::Node {
     let node111 = stretch
@@ -109,9 +110,47 @@ fn build_deep_hierarchy(stretch: &mut stretch::node::Stretch) -> stretch::node::
     let node22 = stretch.new_node(stretch::style::Style { ..Default::default() }, &[node221, node222]).unwrap();
 
     let node2 = stretch.new_node(stretch::style::Style { ..Default::default() }, &[node21, node22]).unwrap();
-    let node0 = stretch.new_node(stretch::style::Style { ..Default::default() }, &[node1, node2]).unwrap();
+    stretch.new_node(stretch::style::Style { ..Default::default() }, &[node1, node2]).unwrap()
+}
+
+fn build_nested_flex_hierarchy(stretch: &mut stretch::node::Stretch, depth: u32) -> stretch::node::Node {
+    if depth == 0 {
+        return stretch
+            .new_leaf(
+                stretch::style::Style { ..Default::default() },
+                stretch::node::MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
+                    width: constraint.width.or_else(20.0),
+                    height: constraint.height.or_else(10.0),
+                }),
+            )
+            .unwrap();
+    }
+
+    let children = [build_nested_flex_hierarchy(stretch, depth - 1), build_nested_flex_hierarchy(stretch, depth - 1)];
+    let flex_direction =
+        [stretch::style::FlexDirection::Row, stretch::style::FlexDirection::Column][depth as usize % 2];
 
-    node0
+    stretch.new_node(stretch::style::Style { flex_direction, flex_grow: 1.0, ..Default::default() }, &children).unwrap()
+}
+
+fn build_card_grid(stretch: &mut stretch::node::Stretch, cards: usize) -> stretch::node::Node {
+    let cards: Vec<_> = (0..cards)
+        .map(|_| {
+            let content = build_nested_flex_hierarchy(stretch, 6);
+            let style = stretch::style::Style {
+                size: stretch::geometry::Size {
+                    width: stretch::style::Dimension::Points(200.0),
+                    height: stretch::style::Dimension::Points(120.0),
+                },
+                flex_shrink: 0.0,
+                ..Default::default()
+            };
+            stretch.new_node(style, &[content]).unwrap()
+        })
+        .collect();
+
+    let style = stretch::style::Style { flex_wrap: stretch::style::FlexWrap::Wrap, ..Default::default() };
+    stretch.new_node(style, &cards).unwrap()
 }
 
 fn stretch_benchmarks(c: &mut Criterion) {
@@ -139,6 +178,31 @@ fn stretch_benchmarks(c: &mut Criterion) {
             stretch.compute_layout(root, stretch::geometry::Size::undefined()).unwrap()
         })
     });
+
+    c.bench_function("nested flex hierarchy - single", |b| {
+        b.iter(|| {
+            let mut stretch = stretch::node::Stretch::new();
+            let root = build_nested_flex_hierarchy(&mut stretch, 8);
+            stretch.compute_layout(root, stretch::geometry::Size::undefined()).unwrap()
+        })
+    });
+
+    let size = stretch::geometry::Size {
+        width: stretch::number::Number::Defined(1000.0),
+        height: stretch::number::Number::Undefined,
+    };
+
+    c.bench_function("card grid - single", |b| {
+        b.iter_batched(
+            || {
+                let mut stretch = stretch::node::Stretch::new();
+                let root = build_card_grid(&mut stretch, 200);
+                (stretch, root)
+            },
+            |(mut stretch, root)| stretch.compute_layout(root, size).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
 }
 
 criterion_group!(benches, stretch_benchmarks);
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_update)]
+
 use criterion::{criterion_group, criterion_main, Criterion};
 mod absolute_layout_align_items_and_justify_content_center;
 mod absolute_layout_align_items_and_justify_content_center_and_bottom_position;
@@ -432,7 +432,7 @@ impl Node {
             .borrow_mut()
             .set_measure(
                 self.node,
-                Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraints| {
+                Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraints, _| {
                     use stretch::number::OrElse;
 
                     let widthConstraint = if let stretch::number::Number::Defined(val) = constraints.width {
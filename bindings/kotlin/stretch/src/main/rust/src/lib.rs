@@ -277,7 +277,7 @@ pub unsafe extern "C" fn Java_app_visly_stretch_Node_nConstructLeaf(
     let node = stretch
         .new_leaf(
             *style,
-            stretch::node::MeasureFunc::Boxed(Box::new(move |constraint| {
+            stretch::node::MeasureFunc::Boxed(Box::new(move |constraint, _| {
                 let result = env.call_method(
                     measure.as_obj(),
                     "measure",
@@ -332,7 +332,7 @@ pub unsafe extern "C" fn Java_app_visly_stretch_Node_nSetMeasure(
     stretch
         .set_measure(
             *node,
-            Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraint| {
+            Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraint, _| {
                 let result = env.call_method(
                     measure.as_obj(),
                     "measure",
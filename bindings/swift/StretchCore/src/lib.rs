@@ -245,7 +245,7 @@ pub unsafe extern "C" fn stretch_node_set_measure(
     stretch
         .set_measure(
             *node,
-            Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraint| {
+            Some(stretch::node::MeasureFunc::Boxed(Box::new(move |constraint, _| {
                 let size = measure(swift_ptr, constraint.width.or_else(f32::NAN), constraint.height.or_else(f32::NAN));
                 size
             }))),
@@ -5,7 +5,6 @@ use std::process::Command;
 use failure::*;
 use fantoccini::{Client, Locator};
 use futures::{future::Future, stream::Stream, sync::oneshot::channel};
-use json;
 use log::*;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
@@ -51,7 +50,7 @@ fn main() {
     info!("spawning webdriver client and collecting test descriptions");
     tokio::run(
         Client::with_capabilities(webdriver_url, caps.clone())
-            .map_err(|e| Error::from(e))
+            .map_err(Error::from)
             .and_then(move |client| {
                 futures::stream::iter_ok(pb.wrap_iter(fixtures.into_iter()))
                     .and_then(move |(name, fixture_path)| {
@@ -71,6 +70,7 @@ fn main() {
 
     info!("killing webdriver instance...");
     webdriver_handle.kill().unwrap();
+    webdriver_handle.wait().unwrap();
 
     info!("collecting test descriptions from async runtime...");
     let test_descs = loop {
@@ -128,6 +128,8 @@ fn main() {
     }
 
     let bench_mods = quote!(
+        #![allow(clippy::needless_update)]
+
         use criterion::{criterion_group, criterion_main, Criterion};
 
         #test_mods
@@ -181,7 +183,7 @@ fn test_root_element(
 }
 
 fn generate_bench(description: &json::JsonValue) -> TokenStream {
-    let node_description = generate_node("node", &description);
+    let node_description = generate_node("node", description);
 
     quote!(
         pub fn compute() {
@@ -195,8 +197,8 @@ fn generate_bench(description: &json::JsonValue) -> TokenStream {
 fn generate_test(name: impl AsRef<str>, description: &json::JsonValue) -> TokenStream {
     let name = name.as_ref();
     let name = Ident::new(name, Span::call_site());
-    let node_description = generate_node("node", &description);
-    let assertions = generate_assertions("node", &description);
+    let node_description = generate_node("node", description);
+    let assertions = generate_assertions("node", description);
 
     quote!(
         #[test]
@@ -220,14 +222,10 @@ fn generate_assertions(ident: &str, node: &json::JsonValue) -> TokenStream {
 
     let children = {
         let mut c = Vec::new();
-        match node["children"] {
-            json::JsonValue::Array(ref value) => {
-                for i in 0..value.len() {
-                    let child = &value[i];
-                    c.push(generate_assertions(&format!("{}{}", ident, i), child));
-                }
+        if let json::JsonValue::Array(ref value) = node["children"] {
+            for (i, child) in value.iter().enumerate() {
+                c.push(generate_assertions(&format!("{}{}", ident, i), child));
             }
-            _ => (),
         };
         c.into_iter().fold(quote!(), |a, b| quote!(#a #b))
     };
@@ -414,7 +412,7 @@ fn generate_node(ident: &str, node: &json::JsonValue) -> TokenStream {
 
     let (children_body, children) = match node["children"] {
         json::JsonValue::Array(ref value) => {
-            if value.len() > 0 {
+            if !value.is_empty() {
                 let body = value
                     .iter()
                     .enumerate()
@@ -433,7 +431,7 @@ fn generate_node(ident: &str, node: &json::JsonValue) -> TokenStream {
         _ => (quote!(), quote!()),
     };
 
-    let ident = Ident::new(&format!("{}", ident), Span::call_site());
+    let ident = Ident::new(ident, Span::call_site());
 
     quote!(
         #children_body
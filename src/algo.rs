@@ -1,6 +1,6 @@
 use core::f32;
 
-use crate::forest::{Forest, NodeData};
+use crate::forest::{Forest, RoundedLayout};
 use crate::id::NodeId;
 use crate::node::MeasureFunc;
 use crate::result;
@@ -54,9 +54,44 @@ struct FlexLine<'a> {
     offset_cross: f32,
 }
 
-impl Forest {
+impl<T> Forest<T> {
     pub(crate) fn compute(&mut self, root: NodeId, size: Size<Number>) {
-        let style = self.nodes[root].style;
+        self.reset_counters();
+        self.compute_root(root, size);
+    }
+
+    /// Lays out several trees, keeping the results cached for nodes shared between them.
+    pub(crate) fn compute_many(&mut self, roots: &[(NodeId, Size<Number>)]) {
+        self.reset_counters();
+        for (root, size) in roots {
+            self.compute_root(*root, *size);
+        }
+    }
+
+    /// Resets the counters describing the work done by a layout pass.
+    fn reset_counters(&mut self) {
+        self.visited_nodes = 0;
+        self.measure_cache_hits = 0;
+
+        #[cfg(feature = "stats")]
+        {
+            self.stats = Default::default();
+            for node in self.nodes.iter_mut() {
+                node.computations = 0;
+            }
+        }
+    }
+
+    fn compute_root(&mut self, root: NodeId, size: Size<Number>) {
+        // When the root keeps its previous layout only the dirty subtrees below it are laid out again.
+        if let Some((node_size, parent_size)) = self.nodes[root].layout_constraints {
+            if parent_size == size && self.cached_result(root, node_size, parent_size, true).is_some() {
+                self.relayout_dirty_subtrees(root, 0.0, 0.0);
+                return;
+            }
+        }
+
+        let style = self.styles[self.nodes[root].style];
         let has_root_min_max = style.min_size.width.is_defined()
             || style.min_size.height.is_defined()
             || style.max_size.width.is_defined()
@@ -88,32 +123,169 @@ impl Forest {
             self.compute_internal(root, style.size.resolve(size), size, true)
         };
 
-        self.nodes[root].layout = result::Layout { order: 0, size: result.size, location: Point::zero() };
+        self.nodes[root].unrounded_layout = result::Layout {
+            order: 0,
+            size: result.size,
+            location: Point::zero(),
+            ..self.nodes[root].unrounded_layout
+        };
 
-        Self::round_layout(&mut self.nodes, &self.children, root, 0.0, 0.0);
+        self.round_layout(root, 0.0, 0.0);
     }
 
-    fn round_layout(
-        nodes: &mut [NodeData],
-        children: &[sys::ChildrenVec<NodeId>],
-        root: NodeId,
-        abs_x: f32,
-        abs_y: f32,
-    ) {
-        let layout = &mut nodes[root].layout;
-        let abs_x = abs_x + layout.location.x;
-        let abs_y = abs_y + layout.location.y;
-
-        layout.location.x = sys::round(layout.location.x);
-        layout.location.y = sys::round(layout.location.y);
-        layout.size.width = sys::round(abs_x + layout.size.width) - sys::round(abs_x);
-        layout.size.height = sys::round(abs_y + layout.size.height) - sys::round(abs_y);
-        for child in &children[root] {
-            Self::round_layout(nodes, children, *child, abs_x, abs_y);
+    pub(crate) fn round_layout(&mut self, root: NodeId, abs_x: f32, abs_y: f32) {
+        let abs_x = abs_x + self.nodes[root].unrounded_layout.location.x;
+        let abs_y = abs_y + self.nodes[root].unrounded_layout.location.y;
+        self.nodes[root].unrounded_layout.absolute_location = Point { x: abs_x, y: abs_y };
+
+        let unrounded = self.nodes[root].unrounded_layout;
+        let rounding = self.rounding;
+        let layout = if let result::Rounding::None = rounding {
+            unrounded
+        } else {
+            let round = |value| rounding.apply(value);
+            result::Layout {
+                order: unrounded.order,
+                size: Size {
+                    width: round(abs_x + unrounded.size.width) - round(abs_x),
+                    height: round(abs_y + unrounded.size.height) - round(abs_y),
+                },
+                location: Point { x: round(unrounded.location.x), y: round(unrounded.location.y) },
+                absolute_location: Point { x: round(abs_x), y: round(abs_y) },
+                margin: unrounded.margin.map(round),
+                border: unrounded.border.map(round),
+                padding: unrounded.padding.map(round),
+            }
+        };
+
+        // Only nodes hidden by `Display::None` are left without layout constraints.
+        let is_hidden = self.nodes[root].layout_constraints.is_none();
+        let rounded = &mut self.layouts[root];
+        if let Some(changed) = &mut self.changed {
+            let mut changes = 0;
+            if is_hidden != rounded.is_hidden {
+                changes |= if is_hidden { RoundedLayout::HIDDEN } else { RoundedLayout::SHOWN };
+            }
+            if !is_hidden && layout.size != rounded.layout.size {
+                changes |= RoundedLayout::RESIZED;
+            }
+            if !is_hidden && layout.location != rounded.layout.location {
+                changes |= RoundedLayout::MOVED;
+            }
+            if changes != 0 && rounded.changes == 0 {
+                changed.push(root);
+            }
+            rounded.changes |= changes;
+        }
+        rounded.is_hidden = is_hidden;
+        rounded.layout = layout;
+
+        for i in 0..self.children[root].len() {
+            self.round_layout(self.children[root][i], abs_x, abs_y);
+        }
+    }
+
+    /// Walks down the dirty flags below a node whose own layout is still valid and lays out
+    /// each subtree starting at the first node without a valid layout, see `Forest::mark_dirty`.
+    /// Only these subtrees are rounded again, at the unrounded position `abs_x`, `abs_y` of the
+    /// parent relative to the root. The position is taken along the walk rather than up the
+    /// parents, as a node may have several.
+    fn relayout_dirty_subtrees(&mut self, node: NodeId, abs_x: f32, abs_y: f32) {
+        if !self.nodes[node].is_dirty {
+            return;
+        }
+
+        let relayout = match self.nodes[node].layout_constraints {
+            Some((node_size, parent_size)) => {
+                if self.cached_result(node, node_size, parent_size, true).is_none() {
+                    self.compute_internal(node, node_size, parent_size, true);
+                    true
+                } else {
+                    false
+                }
+            }
+            // Only hidden nodes are left without layout constraints, their subtree may have changed.
+            None => {
+                self.hidden_layout(node, self.nodes[node].unrounded_layout.order);
+                true
+            }
+        };
+
+        if relayout {
+            self.round_layout(node, abs_x, abs_y);
+            return;
+        }
+
+        self.nodes[node].is_dirty = false;
+        let abs_x = abs_x + self.nodes[node].unrounded_layout.location.x;
+        let abs_y = abs_y + self.nodes[node].unrounded_layout.location.y;
+        for i in 0..self.children[node].len() {
+            self.relayout_dirty_subtrees(self.children[node][i], abs_x, abs_y);
+        }
+    }
+
+    /// Lays out the dirty children of a node whose own size is still valid, using
+    /// the same constraints as their previous layout.
+    fn relayout_dirty_children(&mut self, node: NodeId) {
+        for i in 0..self.children[node].len() {
+            let child = self.children[node][i];
+            if !self.nodes[child].is_dirty {
+                continue;
+            }
+
+            match self.nodes[child].layout_constraints {
+                Some((node_size, parent_size)) => {
+                    self.compute_internal(child, node_size, parent_size, true);
+                }
+                None => self.hidden_layout(child, i as _),
+            }
+        }
+    }
+
+    fn cached_result(
+        &self,
+        node: NodeId,
+        node_size: Size<Number>,
+        parent_size: Size<Number>,
+        perform_layout: bool,
+    ) -> Option<ComputeResult> {
+        let style = &self.styles[self.nodes[node].style];
+        let is_percent = |dimension: &Dimension| matches!(dimension, Dimension::Percent(_));
+        let percent_edges = [style.margin, style.padding, style.border]
+            .iter()
+            .any(|edges| [edges.start, edges.end, edges.top, edges.bottom].iter().any(is_percent));
+
+        let reuse = match self.contents[node].measure {
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            Some(MeasureFunc::Measure(_)) => result::Reuse::Measured { percent_edges },
+            Some(_) if self.children[node].is_empty() => result::Reuse::Leaf { percent_edges },
+            _ => result::Reuse::Container { percent_edges },
+        };
+        self.nodes[node].layout_cache.get(node_size, parent_size, perform_layout, reuse)
+    }
+
+    /// Discards the cached final layout of a node and of all its ancestors.
+    fn clear_final_layouts(&mut self, node: NodeId) {
+        self.nodes[node].layout_cache.clear_final_layout();
+        for i in 0..self.parents[node].len() {
+            self.clear_final_layouts(self.parents[node][i]);
+        }
+    }
+
+    /// Clears the layout of a node hidden by `Display::None` and of its whole subtree.
+    fn hidden_layout(&mut self, node: NodeId, order: u32) {
+        let data = &mut self.nodes[node];
+        data.unrounded_layout = result::Layout { order, ..result::Layout::new() };
+        data.is_dirty = false;
+        data.layout_constraints = None;
+        // The cached results no longer match the layouts of the children, which are cleared.
+        data.layout_cache.clear();
+
+        for order in 0..self.children[node].len() {
+            self.hidden_layout(self.children[node][order], order as _);
         }
     }
 
-    #[allow(clippy::cognitive_complexity)]
     fn compute_internal(
         &mut self,
         node: NodeId,
@@ -121,44 +293,76 @@ impl Forest {
         parent_size: Size<Number>,
         perform_layout: bool,
     ) -> ComputeResult {
-        self.nodes[node].is_dirty = false;
+        #[cfg(feature = "stats")]
+        {
+            self.stats.computations += 1;
+            self.nodes[node].computations += 1;
+            self.depth += 1;
+            self.stats.max_depth = self.stats.max_depth.max(self.depth);
+        }
 
-        // First we check if we have a result for the given input
-        if let Some(ref cache) = self.nodes[node].layout_cache {
-            if cache.perform_layout || !perform_layout {
-                let width_compatible = if let Number::Defined(width) = node_size.width {
-                    sys::abs(width - cache.result.size.width) < f32::EPSILON
-                } else {
-                    cache.node_size.width.is_undefined()
-                };
+        let result = self.compute_internal_impl(node, node_size, parent_size, perform_layout);
 
-                let height_compatible = if let Number::Defined(height) = node_size.height {
-                    sys::abs(height - cache.result.size.height) < f32::EPSILON
-                } else {
-                    cache.node_size.height.is_undefined()
-                };
+        #[cfg(feature = "stats")]
+        {
+            self.depth -= 1;
+        }
 
-                if width_compatible && height_compatible {
-                    return cache.result.clone();
-                }
+        result
+    }
 
-                if cache.node_size == node_size && cache.parent_size == parent_size {
-                    return cache.result.clone();
-                }
+    #[allow(clippy::cognitive_complexity)]
+    fn compute_internal_impl(
+        &mut self,
+        node: NodeId,
+        node_size: Size<Number>,
+        parent_size: Size<Number>,
+        perform_layout: bool,
+    ) -> ComputeResult {
+        // Dirty nodes with a valid cache have a dirty subtree below them, see `Forest::mark_dirty`.
+        let has_dirty_subtree = self.nodes[node].is_dirty;
+        if perform_layout {
+            self.visited_nodes += 1;
+            self.nodes[node].is_dirty = false;
+            self.nodes[node].layout_constraints = Some((node_size, parent_size));
+        }
+
+        // First we check if we have a result for the given input
+        if let Some(result) = self.cached_result(node, node_size, parent_size, perform_layout) {
+            #[cfg(feature = "stats")]
+            {
+                self.stats.cache_hits += 1;
             }
+
+            if perform_layout && has_dirty_subtree {
+                self.relayout_dirty_children(node);
+            }
+            return result;
+        }
+
+        #[cfg(feature = "stats")]
+        {
+            self.stats.cache_misses += 1;
         }
 
         // Define some general constants we will need for the remainder
         // of the algorithm.
 
-        let dir = self.nodes[node].style.flex_direction;
+        let dir = self.styles[self.nodes[node].style].flex_direction;
         let is_row = dir.is_row();
         let is_column = dir.is_column();
-        let is_wrap_reverse = self.nodes[node].style.flex_wrap == FlexWrap::WrapReverse;
+        let is_wrap_reverse = self.styles[self.nodes[node].style].flex_wrap == FlexWrap::WrapReverse;
 
-        let margin = self.nodes[node].style.margin.map(|n| n.resolve(parent_size.width).or_else(0.0));
-        let padding = self.nodes[node].style.padding.map(|n| n.resolve(parent_size.width).or_else(0.0));
-        let border = self.nodes[node].style.border.map(|n| n.resolve(parent_size.width).or_else(0.0));
+        let margin = self.styles[self.nodes[node].style].margin.map(|n| n.resolve(parent_size.width).or_else(0.0));
+        let padding = self.styles[self.nodes[node].style].padding.map(|n| n.resolve(parent_size.width).or_else(0.0));
+        let border = self.styles[self.nodes[node].style].border.map(|n| n.resolve(parent_size.width).or_else(0.0));
+
+        if perform_layout {
+            let layout = &mut self.nodes[node].unrounded_layout;
+            layout.margin = margin;
+            layout.border = border;
+            layout.padding = padding;
+        }
 
         let padding_border = Rect {
             start: padding.start + border.start,
@@ -181,14 +385,58 @@ impl Forest {
                 return ComputeResult { size: node_size.map(|s| s.or_else(0.0)) };
             }
 
-            if let Some(ref measure) = self.nodes[node].measure {
-                let result = match measure {
-                    MeasureFunc::Raw(measure) => ComputeResult { size: measure(node_size) },
+            let content = &mut self.contents[node];
+            if let Some(ref mut measure) = content.measure {
+                // Only `Measure` implementations see the available space, so it is not part of
+                // the cache key for other measure functions. While its size is not known yet a leaf
+                // is given the inner size of its parent, of which its margins take up some.
+                let available = match measure {
                     #[cfg(any(feature = "std", feature = "alloc"))]
-                    MeasureFunc::Boxed(measure) => ComputeResult { size: measure(node_size) },
+                    MeasureFunc::Measure(_) => Size {
+                        width: (parent_size.width - margin.horizontal()).maybe_max(0.0),
+                        height: (parent_size.height - margin.vertical()).maybe_max(0.0),
+                    },
+                    _ => Size::undefined(),
+                };
+
+                let size = if let Some(size) = content.measure_cache.get(node_size, available) {
+                    self.measure_cache_hits += 1;
+                    size
+                } else {
+                    // Pending and intrinsic sizes are known without calling into the user's code.
+                    #[cfg(feature = "stats")]
+                    if !matches!(measure, MeasureFunc::Pending(_) | MeasureFunc::Intrinsic(_)) {
+                        self.stats.measure_calls += 1;
+                    }
+
+                    let size = match measure {
+                        MeasureFunc::Raw(measure) => measure(node_size, &content.context),
+                        #[cfg(any(feature = "std", feature = "alloc"))]
+                        MeasureFunc::Boxed(measure) => measure(node_size, &content.context),
+                        MeasureFunc::Pending(size) | MeasureFunc::Intrinsic(size) => Size {
+                            width: node_size.width.or_else(size.width),
+                            height: node_size.height.or_else(size.height),
+                        },
+                        #[cfg(any(feature = "std", feature = "alloc"))]
+                        MeasureFunc::Measure(measure) => {
+                            match measure.measure(content.node, node_size, available.map(Into::into)) {
+                                Ok(size) => size,
+                                Err(error) => {
+                                    // Failed measurements are not cached, so the node is measured again next time.
+                                    if self.measure_error.is_none() {
+                                        self.measure_error = Some((node, error));
+                                    }
+                                    return ComputeResult { size: Size::zero() };
+                                }
+                            }
+                        }
+                    };
+                    content.measure_cache.insert(node_size, available, size);
+                    size
                 };
-                self.nodes[node].layout_cache =
-                    Some(result::Cache { node_size, parent_size, perform_layout, result: result.clone() });
+
+                let result = ComputeResult { size };
+                self.nodes[node].layout_cache.insert(node_size, parent_size, perform_layout, result.clone());
                 return result;
             }
 
@@ -219,7 +467,7 @@ impl Forest {
 
         let mut flex_items: sys::Vec<FlexItem> = self.children[node]
             .iter()
-            .map(|child| (child, &self.nodes[*child].style))
+            .map(|child| (child, &self.styles[self.nodes[*child].style]))
             .filter(|(_, style)| style.position_type != PositionType::Absolute)
             .filter(|(_, style)| style.display != Display::None)
             .map(|(child, child_style)| FlexItem {
@@ -250,14 +498,15 @@ impl Forest {
             })
             .collect();
 
-        let has_baseline_child = flex_items
-            .iter()
-            .any(|child| self.nodes[child.node].style.align_self(&self.nodes[node].style) == AlignSelf::Baseline);
+        let has_baseline_child = flex_items.iter().any(|child| {
+            self.styles[self.nodes[child.node].style].align_self(&self.styles[self.nodes[node].style])
+                == AlignSelf::Baseline
+        });
 
         // TODO - this does not follow spec. See commented out code below
         // 3. Determine the flex base size and hypothetical main size of each item:
         for child in &mut flex_items {
-            let child_style = self.nodes[child.node].style;
+            let child_style = self.styles[self.nodes[child.node].style];
 
             // A. If the item has a definite used flex basis, that’s the flex base size.
 
@@ -305,7 +554,7 @@ impl Forest {
             //    flex item’s cross size. The flex base size is the item’s resulting main size.
 
             let width: Number = if !child.size.width.is_defined()
-                && child_style.align_self(&self.nodes[node].style) == AlignSelf::Stretch
+                && child_style.align_self(&self.styles[self.nodes[node].style]) == AlignSelf::Stretch
                 && is_column
             {
                 available_space.width
@@ -314,7 +563,7 @@ impl Forest {
             };
 
             let height: Number = if !child.size.height.is_defined()
-                && child_style.align_self(&self.nodes[node].style) == AlignSelf::Stretch
+                && child_style.align_self(&self.styles[self.nodes[node].style]) == AlignSelf::Stretch
                 && is_row
             {
                 available_space.height
@@ -385,7 +634,7 @@ impl Forest {
         let mut flex_lines: sys::Vec<_> = {
             let mut lines = sys::new_vec_with_capacity(1);
 
-            if self.nodes[node].style.flex_wrap == FlexWrap::NoWrap {
+            if self.styles[self.nodes[node].style].flex_wrap == FlexWrap::NoWrap {
                 lines.push(FlexLine { items: flex_items.as_mut_slice(), cross_size: 0.0, offset_cross: 0.0 });
             } else {
                 let mut flex_items = &mut flex_items[..];
@@ -395,7 +644,7 @@ impl Forest {
                     let index = flex_items
                         .iter()
                         .enumerate()
-                        .find(|&(idx, ref child)| {
+                        .find(|&(idx, child)| {
                             line_length += child.hypothetical_outer_size.main(dir);
                             if let Defined(main) = available_space.main(dir) {
                                 line_length > main && idx != 0
@@ -470,7 +719,7 @@ impl Forest {
 
                 child.outer_target_size.set_main(dir, child.target_size.main(dir) + child.margin.main(dir));
 
-                let child_style = &self.nodes[child.node].style;
+                let child_style = &self.styles[self.nodes[child.node].style];
                 if (child_style.flex_grow == 0.0 && child_style.flex_shrink == 0.0)
                     || (growing && child.flex_basis > child.hypothetical_inner_size.main(dir))
                     || (shrinking && child.flex_basis < child.hypothetical_inner_size.main(dir))
@@ -523,7 +772,7 @@ impl Forest {
 
                 let (sum_flex_grow, sum_flex_shrink): (f32, f32) =
                     unfrozen.iter().fold((0.0, 0.0), |(flex_grow, flex_shrink), item| {
-                        let style = &self.nodes[item.node].style;
+                        let style = &self.styles[self.nodes[item.node].style];
                         (flex_grow + style.flex_grow, flex_shrink + style.flex_shrink)
                     });
 
@@ -560,19 +809,20 @@ impl Forest {
                             child.target_size.set_main(
                                 dir,
                                 child.flex_basis
-                                    + free_space * (self.nodes[child.node].style.flex_grow / sum_flex_grow),
+                                    + free_space
+                                        * (self.styles[self.nodes[child.node].style].flex_grow / sum_flex_grow),
                             );
                         }
                     } else if shrinking && sum_flex_shrink > 0.0 {
                         let sum_scaled_shrink_factor: f32 = unfrozen
                             .iter()
-                            .map(|child| child.inner_flex_basis * self.nodes[child.node].style.flex_shrink)
+                            .map(|child| child.inner_flex_basis * self.styles[self.nodes[child.node].style].flex_shrink)
                             .sum();
 
                         if sum_scaled_shrink_factor > 0.0 {
                             for child in &mut unfrozen {
                                 let scaled_shrink_factor =
-                                    child.inner_flex_basis * self.nodes[child.node].style.flex_shrink;
+                                    child.inner_flex_basis * self.styles[self.nodes[child.node].style].flex_shrink;
                                 child.target_size.set_main(
                                     dir,
                                     child.flex_basis + free_space * (scaled_shrink_factor / sum_scaled_shrink_factor),
@@ -593,7 +843,7 @@ impl Forest {
                     // webkit handled various scenarios. Can probably be solved better by passing in
                     // min-content max-content constraints from the top. Need to figure out correct thing to do here as
                     // just piling on more conditionals.
-                    let min_main = if is_row && self.nodes[child.node].measure.is_none() {
+                    let min_main = if is_row && self.contents[child.node].measure.is_none() {
                         self.compute_internal(child.node, Size::undefined(), available_space, false)
                             .size
                             .width
@@ -692,16 +942,22 @@ impl Forest {
         // TODO - probably should move this somewhere else as it doesn't make a ton of sense here but we need it below
         // TODO - This is expensive and should only be done if we really require a baseline. aka, make it lazy
 
-        fn calc_baseline(db: &Forest, node: NodeId, layout: &result::Layout) -> f32 {
+        fn calc_baseline<T>(db: &Forest<T>, node: NodeId, layout: &result::Layout) -> f32 {
             if db.children[node].is_empty() {
                 layout.size.height
             } else {
                 let child = db.children[node][0];
-                calc_baseline(db, child, &db.nodes[child].layout)
+                calc_baseline(db, child, &db.nodes[child].unrounded_layout)
             }
-        };
+        }
 
         if has_baseline_child {
+            // Finding the baselines lays out the children, so when only measuring this node the
+            // cached final layouts of it and its ancestors no longer match the layouts below them.
+            if !perform_layout {
+                self.clear_final_layouts(node);
+            }
+
             for line in &mut flex_lines {
                 for child in line.items.iter_mut() {
                     let result = self.compute_internal(
@@ -731,7 +987,7 @@ impl Forest {
                         &result::Layout {
                             order: self.children[node].iter().position(|n| *n == child.node).unwrap() as u32,
                             size: result.size,
-                            location: Point::zero(),
+                            ..result::Layout::new()
                         },
                     );
                 }
@@ -767,8 +1023,8 @@ impl Forest {
                     .items
                     .iter()
                     .map(|child| {
-                        let child_style = &self.nodes[child.node].style;
-                        if child_style.align_self(&self.nodes[node].style) == AlignSelf::Baseline
+                        let child_style = &self.styles[self.nodes[child.node].style];
+                        if child_style.align_self(&self.styles[self.nodes[node].style]) == AlignSelf::Baseline
                             && child_style.cross_margin_start(dir) != Dimension::Auto
                             && child_style.cross_margin_end(dir) != Dimension::Auto
                             && child_style.cross_size(dir) == Dimension::Auto
@@ -788,7 +1044,9 @@ impl Forest {
         //    by equal amounts such that the sum of their cross sizes exactly equals the
         //    flex container’s inner cross size.
 
-        if self.nodes[node].style.align_content == AlignContent::Stretch && node_size.cross(dir).is_defined() {
+        if self.styles[self.nodes[node].style].align_content == AlignContent::Stretch
+            && node_size.cross(dir).is_defined()
+        {
             let total_cross: f32 = flex_lines.iter().map(|line| line.cross_size).sum();
             let inner_cross = (node_size.cross(dir) - padding_border.cross(dir)).or_else(0.0);
 
@@ -830,10 +1088,10 @@ impl Forest {
             let line_cross_size = line.cross_size;
 
             for child in line.items.iter_mut() {
-                let child_style = &self.nodes[child.node].style;
+                let child_style = &self.styles[self.nodes[child.node].style];
                 child.target_size.set_cross(
                     dir,
-                    if child_style.align_self(&self.nodes[node].style) == AlignSelf::Stretch
+                    if child_style.align_self(&self.styles[self.nodes[node].style]) == AlignSelf::Stretch
                         && child_style.cross_margin_start(dir) != Dimension::Auto
                         && child_style.cross_margin_end(dir) != Dimension::Auto
                         && child_style.cross_size(dir) == Dimension::Auto
@@ -864,7 +1122,7 @@ impl Forest {
             let mut num_auto_margins = 0;
 
             for child in line.items.iter_mut() {
-                let child_style = &self.nodes[child.node].style;
+                let child_style = &self.styles[self.nodes[child.node].style];
                 if child_style.main_margin_start(dir) == Dimension::Auto {
                     num_auto_margins += 1;
                 }
@@ -877,7 +1135,7 @@ impl Forest {
                 let margin = free_space / num_auto_margins as f32;
 
                 for child in line.items.iter_mut() {
-                    let child_style = &self.nodes[child.node].style;
+                    let child_style = &self.styles[self.nodes[child.node].style];
                     if child_style.main_margin_start(dir) == Dimension::Auto {
                         if is_row {
                             child.margin.start = margin;
@@ -900,7 +1158,7 @@ impl Forest {
                 let justify_item = |(i, child): (usize, &mut FlexItem)| {
                     let is_first = i == 0;
 
-                    child.offset_main = match self.nodes[node].style.justify_content {
+                    child.offset_main = match self.styles[self.nodes[node].style].justify_content {
                         JustifyContent::FlexStart => {
                             if layout_reverse && is_first {
                                 free_space
@@ -964,7 +1222,7 @@ impl Forest {
 
             for child in line.items.iter_mut() {
                 let free_space = line_cross_size - child.outer_target_size.cross(dir);
-                let child_style = &self.nodes[child.node].style;
+                let child_style = &self.styles[self.nodes[child.node].style];
 
                 if child_style.cross_margin_start(dir) == Dimension::Auto
                     && child_style.cross_margin_end(dir) == Dimension::Auto
@@ -992,7 +1250,7 @@ impl Forest {
                     // 14. Align all flex items along the cross-axis per align-self, if neither of the item’s
                     //     cross-axis margins are auto.
 
-                    child.offset_cross = match child_style.align_self(&self.nodes[node].style) {
+                    child.offset_cross = match child_style.align_self(&self.styles[self.nodes[node].style]) {
                         AlignSelf::Auto => 0.0, // Should never happen
                         AlignSelf::FlexStart => {
                             if is_wrap_reverse {
@@ -1048,8 +1306,7 @@ impl Forest {
         // layout we are done now.
         if !perform_layout {
             let result = ComputeResult { size: container_size };
-            self.nodes[node].layout_cache =
-                Some(result::Cache { node_size, parent_size, perform_layout, result: result.clone() });
+            self.nodes[node].layout_cache.insert(node_size, parent_size, perform_layout, result.clone());
             return result;
         }
 
@@ -1061,7 +1318,7 @@ impl Forest {
         let align_line = |(i, line): (usize, &mut FlexLine)| {
             let is_first = i == 0;
 
-            line.offset_cross = match self.nodes[node].style.align_content {
+            line.offset_cross = match self.styles[self.nodes[node].style].align_content {
                 AlignContent::FlexStart => {
                     if is_first && is_wrap_reverse {
                         free_space
@@ -1134,13 +1391,14 @@ impl Forest {
                         + child.margin.cross_start(dir)
                         + (child.position.cross_start(dir).or_else(0.0) - child.position.cross_end(dir).or_else(0.0));
 
-                    self.nodes[child.node].layout = result::Layout {
+                    self.nodes[child.node].unrounded_layout = result::Layout {
                         order: self.children[node].iter().position(|n| *n == child.node).unwrap() as u32,
                         size: result.size,
                         location: Point {
                             x: if is_row { offset_main } else { offset_cross },
                             y: if is_column { offset_main } else { offset_cross },
                         },
+                        ..self.nodes[child.node].unrounded_layout
                     };
 
                     total_offset_main += child.offset_main + child.margin.main(dir) + result.size.main(dir);
@@ -1169,14 +1427,14 @@ impl Forest {
                 .iter()
                 .cloned()
                 .enumerate()
-                .filter(|(_, child)| self.nodes[*child].style.position_type == PositionType::Absolute)
+                .filter(|(_, child)| self.styles[self.nodes[*child].style].position_type == PositionType::Absolute)
                 .collect::<sys::Vec<_>>();
 
             for (order, child) in candidates {
                 let container_width = container_size.width.into();
                 let container_height = container_size.height.into();
 
-                let child_style = self.nodes[child].style;
+                let child_style = self.styles[self.nodes[child].style];
 
                 let start = child_style.position.start.resolve(container_width)
                     + child_style.margin.start.resolve(container_width);
@@ -1240,7 +1498,7 @@ impl Forest {
                 } else if end_main.is_defined() {
                     free_main_space - end_main.or_else(0.0) - border.main_end(dir)
                 } else {
-                    match self.nodes[node].style.justify_content {
+                    match self.styles[self.nodes[node].style].justify_content {
                         JustifyContent::SpaceBetween | JustifyContent::FlexStart => padding_border.main_start(dir),
                         JustifyContent::FlexEnd => free_main_space - padding_border.main_end(dir),
                         JustifyContent::SpaceEvenly | JustifyContent::SpaceAround | JustifyContent::Center => {
@@ -1254,7 +1512,7 @@ impl Forest {
                 } else if end_cross.is_defined() {
                     free_cross_space - end_cross.or_else(0.0) - border.cross_end(dir)
                 } else {
-                    match child_style.align_self(&self.nodes[node].style) {
+                    match child_style.align_self(&self.styles[self.nodes[node].style]) {
                         AlignSelf::Auto => 0.0, // Should never happen
                         AlignSelf::FlexStart => {
                             if is_wrap_reverse {
@@ -1282,34 +1540,27 @@ impl Forest {
                     }
                 };
 
-                self.nodes[child].layout = result::Layout {
+                self.nodes[child].unrounded_layout = result::Layout {
                     order: order as u32,
                     size: result.size,
                     location: Point {
                         x: if is_row { offset_main } else { offset_cross },
                         y: if is_column { offset_main } else { offset_cross },
                     },
+                    ..self.nodes[child].unrounded_layout
                 };
             }
         }
 
-        fn hidden_layout(nodes: &mut [NodeData], children: &[sys::ChildrenVec<NodeId>], node: NodeId, order: u32) {
-            nodes[node].layout = result::Layout { order, size: Size::zero(), location: Point::zero() };
-
-            for (order, child) in children[node].iter().enumerate() {
-                hidden_layout(nodes, children, *child, order as _);
-            }
-        }
-
-        for (order, child) in self.children[node].iter().enumerate() {
-            if self.nodes[*child].style.display == Display::None {
-                hidden_layout(&mut self.nodes, &self.children, *child, order as _);
+        for order in 0..self.children[node].len() {
+            let child = self.children[node][order];
+            if self.styles[self.nodes[child].style].display == Display::None {
+                self.hidden_layout(child, order as _);
             }
         }
 
         let result = ComputeResult { size: container_size };
-        self.nodes[node].layout_cache =
-            Some(result::Cache { node_size, parent_size, perform_layout, result: result.clone() });
+        self.nodes[node].layout_cache.insert(node_size, parent_size, perform_layout, result.clone());
 
         result
     }
@@ -0,0 +1,67 @@
+//! Interpolation between computed layouts, to animate a change of layout.
+
+use crate::node::{Node, Stretch};
+use crate::result::Layout;
+use crate::style::Display;
+use crate::sys;
+use crate::Error;
+
+/// The layouts of a subtree at one point in time, see `Stretch::layout_snapshot`.
+pub struct LayoutSnapshot {
+    /// Layouts in tree order.
+    layouts: sys::Vec<(Node, Layout)>,
+    /// Index of each node in `layouts`.
+    indices: sys::Map<Node, usize>,
+}
+
+impl<T> Stretch<T> {
+    /// Captures the layouts of `node` and its descendants. Nodes with `Display::None` and
+    /// their children are left out, so hiding a node counts as removing it.
+    pub fn layout_snapshot(&self, node: Node) -> Result<LayoutSnapshot, Error> {
+        self.layout(node)?;
+        let mut snapshot = LayoutSnapshot { layouts: sys::Vec::new(), indices: sys::new_map_with_capacity(0) };
+        self.capture_layouts(&mut snapshot, node);
+        Ok(snapshot)
+    }
+
+    fn capture_layouts(&self, snapshot: &mut LayoutSnapshot, node: Node) {
+        // Nodes of the subtree are always valid while the stretch instance is borrowed.
+        if self.style(node).unwrap().display == Display::None {
+            return;
+        }
+
+        let _ = snapshot.indices.insert(node, snapshot.layouts.len());
+        snapshot.layouts.push((node, *self.layout(node).unwrap()));
+        for child in self.children(node).unwrap() {
+            self.capture_layouts(snapshot, child);
+        }
+    }
+}
+
+impl LayoutSnapshot {
+    /// Returns the layout `node` had when the snapshot was taken.
+    pub fn get(&self, node: Node) -> Option<&Layout> {
+        self.indices.get(&node).map(|index| &self.layouts[*index].1)
+    }
+
+    /// Returns the nodes of the snapshot in tree order.
+    pub fn nodes(&self) -> impl Iterator<Item = Node> + '_ {
+        self.layouts.iter().map(|(node, _)| *node)
+    }
+
+    /// Returns the layouts at progress `t` from this snapshot to `to`, see `Layout::interpolate`.
+    /// Only nodes found in both snapshots are included, in the tree order of `to`.
+    pub fn interpolate(&self, to: &LayoutSnapshot, t: f32) -> sys::Vec<(Node, Layout)> {
+        to.layouts.iter().filter_map(|(node, layout)| Some((*node, self.get(*node)?.interpolate(layout, t)))).collect()
+    }
+
+    /// Returns the nodes of `to` which are not part of this snapshot.
+    pub fn inserted(&self, to: &LayoutSnapshot) -> sys::Vec<Node> {
+        to.nodes().filter(|node| self.get(*node).is_none()).collect()
+    }
+
+    /// Returns the nodes of this snapshot which are not part of `to`.
+    pub fn removed(&self, to: &LayoutSnapshot) -> sys::Vec<Node> {
+        to.inserted(self)
+    }
+}
@@ -0,0 +1,632 @@
+//! Conversion of `Style` from and to CSS declarations such as `flex-direction: column; padding: 8px 16px`.
+//!
+//! Only the properties stretch supports are accepted. Lengths are given in `px` or `%`,
+//! `left`, `right`, `top` and `bottom` map to `Style::position`. Property names are case
+//! insensitive and `/* */` comments are skipped.
+
+use core::fmt;
+
+use arrayvec::{ArrayString, ArrayVec};
+
+use crate::geometry::Rect;
+use crate::number::Number;
+use crate::style::*;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::sys;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CssErrorKind {
+    /// A declaration without a `:` between property and value.
+    ExpectedColon,
+    /// A declaration without a value.
+    ExpectedValue,
+    UnknownProperty,
+    InvalidValue,
+    /// More values than the property accepts.
+    UnexpectedValue,
+}
+
+/// An error in a CSS string. Lines and columns start at 1 and count characters.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CssError {
+    pub kind: CssErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl CssError {
+    /// Creates an error located at `at`, which has to be a slice of `css`.
+    fn new(kind: CssErrorKind, css: &str, at: &str) -> Self {
+        let (line, column) = position(css, at);
+        CssError { kind, line, column }
+    }
+}
+
+/// Line and column of `at` within `source`, which `at` has to be a slice of.
+pub(crate) fn position(source: &str, at: &str) -> (usize, usize) {
+    let offset = at.as_ptr() as usize - source.as_ptr() as usize;
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for CssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = match self.kind {
+            CssErrorKind::ExpectedColon => "Expected `:` after property",
+            CssErrorKind::ExpectedValue => "Expected a value",
+            CssErrorKind::UnknownProperty => "Unknown property",
+            CssErrorKind::InvalidValue => "Invalid value",
+            CssErrorKind::UnexpectedValue => "Unexpected value",
+        };
+        write!(f, "{} at line {}, column {}", message, self.line, self.column)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CssError {}
+
+impl Style {
+    /// Parses a list of CSS declarations, starting from the default style.
+    pub fn from_css(css: &str) -> Result<Style, CssError> {
+        let mut style = Style::default();
+        for declaration in declarations(css) {
+            Parser { css }.declaration(&mut style, declaration)?;
+        }
+        Ok(style)
+    }
+
+    /// Parses `css`, a slice of `source`, skipping invalid declarations like browsers do.
+    /// Their errors are passed to `invalid`, located within `source`.
+    #[cfg(feature = "html")]
+    pub(crate) fn from_css_lenient<'a>(source: &'a str, css: &'a str, invalid: &mut impl FnMut(CssError)) -> Style {
+        let mut style = Style::default();
+        for declaration in declarations(css) {
+            if let Err(error) = (Parser { css: source }).declaration(&mut style, declaration) {
+                invalid(error);
+            }
+        }
+        style
+    }
+}
+
+/// Values of a single declaration, shorthands take at most four.
+type Values<'a> = ArrayVec<[&'a str; 4]>;
+
+/// Splits `css` at each `;` outside of a comment.
+fn declarations(css: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(css);
+    core::iter::from_fn(move || {
+        let css = rest?;
+        let bytes = css.as_bytes();
+        let mut index = 0;
+        while index < bytes.len() {
+            match bytes[index] {
+                b';' => {
+                    rest = Some(&css[index + 1..]);
+                    return Some(&css[..index]);
+                }
+                b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                    index += css[index + 2..].find("*/").map_or(bytes.len(), |end| end + 4);
+                }
+                _ => index += 1,
+            }
+        }
+        rest = None;
+        Some(css)
+    })
+}
+
+/// Tokens of a declaration, separated by whitespace and comments. `:` and `/` are tokens of
+/// their own, so `16/9` and `16 / 9` are read the same.
+struct Tokens<'a>(&'a str);
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            let rest = self.0.trim_start();
+            if let Some(comment) = rest.strip_prefix("/*") {
+                // An unterminated comment runs to the end of the declaration.
+                self.0 = comment.find("*/").map_or(&comment[comment.len()..], |end| &comment[end + 2..]);
+                continue;
+            }
+
+            let bytes = rest.as_bytes();
+            let len = match bytes.first()? {
+                b':' | b'/' => 1,
+                _ => bytes
+                    .iter()
+                    .position(|byte| byte.is_ascii_whitespace() || *byte == b':' || *byte == b'/')
+                    .unwrap_or(bytes.len()),
+            };
+            self.0 = &rest[len..];
+            return Some(&rest[..len]);
+        }
+    }
+}
+
+struct Parser<'a> {
+    css: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: CssErrorKind, at: &str) -> CssError {
+        CssError::new(kind, self.css, at)
+    }
+
+    fn declaration(&self, style: &mut Style, declaration: &'a str) -> Result<(), CssError> {
+        let mut tokens = Tokens(declaration);
+        let property = match tokens.next() {
+            Some(property) => property,
+            None => return Ok(()),
+        };
+        let colon = match tokens.next() {
+            Some(colon) if colon == ":" => colon,
+            _ => return Err(self.error(CssErrorKind::ExpectedColon, property)),
+        };
+
+        let mut values = Values::new();
+        for token in tokens {
+            values.try_push(token).map_err(|_| self.error(CssErrorKind::UnexpectedValue, token))?;
+        }
+        if values.is_empty() {
+            return Err(self.error(CssErrorKind::ExpectedValue, &colon[1..]));
+        }
+
+        // Longer names than fit are not known properties either.
+        let mut name = ArrayString::<[u8; 32]>::new();
+        for c in property.chars() {
+            name.try_push(c.to_ascii_lowercase()).map_err(|_| self.error(CssErrorKind::UnknownProperty, property))?;
+        }
+
+        match name.as_str() {
+            "display" => style.display = self.keyword(&values, &[("flex", Display::Flex), ("none", Display::None)])?,
+            "position" => {
+                style.position_type = self
+                    .keyword(&values, &[("relative", PositionType::Relative), ("absolute", PositionType::Absolute)])?
+            }
+            "direction" => {
+                style.direction = self.keyword(
+                    &values,
+                    &[("inherit", Direction::Inherit), ("ltr", Direction::LTR), ("rtl", Direction::RTL)],
+                )?
+            }
+            "flex-direction" => {
+                style.flex_direction = self.keyword(
+                    &values,
+                    &[
+                        ("row", FlexDirection::Row),
+                        ("column", FlexDirection::Column),
+                        ("row-reverse", FlexDirection::RowReverse),
+                        ("column-reverse", FlexDirection::ColumnReverse),
+                    ],
+                )?
+            }
+            "flex-wrap" => {
+                style.flex_wrap = self.keyword(
+                    &values,
+                    &[("nowrap", FlexWrap::NoWrap), ("wrap", FlexWrap::Wrap), ("wrap-reverse", FlexWrap::WrapReverse)],
+                )?
+            }
+            "overflow" => {
+                style.overflow = self.keyword(
+                    &values,
+                    &[("visible", Overflow::Visible), ("hidden", Overflow::Hidden), ("scroll", Overflow::Scroll)],
+                )?
+            }
+            "align-items" => {
+                style.align_items = self.keyword(
+                    &values,
+                    &[
+                        ("flex-start", AlignItems::FlexStart),
+                        ("flex-end", AlignItems::FlexEnd),
+                        ("center", AlignItems::Center),
+                        ("baseline", AlignItems::Baseline),
+                        ("stretch", AlignItems::Stretch),
+                    ],
+                )?
+            }
+            "align-self" => {
+                style.align_self = self.keyword(
+                    &values,
+                    &[
+                        ("auto", AlignSelf::Auto),
+                        ("flex-start", AlignSelf::FlexStart),
+                        ("flex-end", AlignSelf::FlexEnd),
+                        ("center", AlignSelf::Center),
+                        ("baseline", AlignSelf::Baseline),
+                        ("stretch", AlignSelf::Stretch),
+                    ],
+                )?
+            }
+            "align-content" => {
+                style.align_content = self.keyword(
+                    &values,
+                    &[
+                        ("flex-start", AlignContent::FlexStart),
+                        ("flex-end", AlignContent::FlexEnd),
+                        ("center", AlignContent::Center),
+                        ("stretch", AlignContent::Stretch),
+                        ("space-between", AlignContent::SpaceBetween),
+                        ("space-around", AlignContent::SpaceAround),
+                    ],
+                )?
+            }
+            "justify-content" => {
+                style.justify_content = self.keyword(
+                    &values,
+                    &[
+                        ("flex-start", JustifyContent::FlexStart),
+                        ("flex-end", JustifyContent::FlexEnd),
+                        ("center", JustifyContent::Center),
+                        ("space-between", JustifyContent::SpaceBetween),
+                        ("space-around", JustifyContent::SpaceAround),
+                        ("space-evenly", JustifyContent::SpaceEvenly),
+                    ],
+                )?
+            }
+
+            "left" => style.position.start = self.single_dimension(&values)?,
+            "right" => style.position.end = self.single_dimension(&values)?,
+            "top" => style.position.top = self.single_dimension(&values)?,
+            "bottom" => style.position.bottom = self.single_dimension(&values)?,
+            "inset" => style.position = self.edges(&values)?,
+
+            "margin" => style.margin = self.edges(&values)?,
+            "margin-left" => style.margin.start = self.single_dimension(&values)?,
+            "margin-right" => style.margin.end = self.single_dimension(&values)?,
+            "margin-top" => style.margin.top = self.single_dimension(&values)?,
+            "margin-bottom" => style.margin.bottom = self.single_dimension(&values)?,
+
+            "padding" => style.padding = self.edges(&values)?,
+            "padding-left" => style.padding.start = self.single_dimension(&values)?,
+            "padding-right" => style.padding.end = self.single_dimension(&values)?,
+            "padding-top" => style.padding.top = self.single_dimension(&values)?,
+            "padding-bottom" => style.padding.bottom = self.single_dimension(&values)?,
+
+            "border-width" => style.border = self.edges(&values)?,
+            "border-left-width" => style.border.start = self.single_dimension(&values)?,
+            "border-right-width" => style.border.end = self.single_dimension(&values)?,
+            "border-top-width" => style.border.top = self.single_dimension(&values)?,
+            "border-bottom-width" => style.border.bottom = self.single_dimension(&values)?,
+
+            "flex" => self.flex(style, &values)?,
+            "flex-grow" => style.flex_grow = self.single_number(&values)?,
+            "flex-shrink" => style.flex_shrink = self.single_number(&values)?,
+            "flex-basis" => style.flex_basis = self.single_dimension(&values)?,
+
+            "width" => style.size.width = self.single_dimension(&values)?,
+            "height" => style.size.height = self.single_dimension(&values)?,
+            "min-width" => style.min_size.width = self.single_dimension(&values)?,
+            "min-height" => style.min_size.height = self.single_dimension(&values)?,
+            "max-width" => style.max_size.width = self.single_dimension(&values)?,
+            "max-height" => style.max_size.height = self.single_dimension(&values)?,
+
+            "aspect-ratio" => style.aspect_ratio = self.aspect_ratio(&values)?,
+
+            _ => return Err(self.error(CssErrorKind::UnknownProperty, property)),
+        }
+
+        Ok(())
+    }
+
+    fn single<'v>(&self, values: &'v Values<'a>) -> Result<&'v &'a str, CssError> {
+        match values.get(1) {
+            Some(extra) => Err(self.error(CssErrorKind::UnexpectedValue, extra)),
+            None => Ok(&values[0]),
+        }
+    }
+
+    fn keyword<T: Copy>(&self, values: &Values<'a>, keywords: &[(&str, T)]) -> Result<T, CssError> {
+        let value = self.single(values)?;
+        match keywords.iter().find(|(keyword, _)| keyword.eq_ignore_ascii_case(value)) {
+            Some((_, result)) => Ok(*result),
+            None => Err(self.error(CssErrorKind::InvalidValue, value)),
+        }
+    }
+
+    fn number(&self, value: &str) -> Result<f32, CssError> {
+        finite(value).ok_or_else(|| self.error(CssErrorKind::InvalidValue, value))
+    }
+
+    fn single_number(&self, values: &Values<'a>) -> Result<f32, CssError> {
+        self.number(self.single(values)?)
+    }
+
+    /// Parses `auto`, a length in `px` or a percentage. Zero is the only unitless length.
+    fn dimension(&self, value: &str) -> Result<Dimension, CssError> {
+        let dimension = if value.eq_ignore_ascii_case("auto") {
+            Some(Dimension::Auto)
+        } else if let Some(points) = strip_unit(value, "px") {
+            finite(points).map(Dimension::Points)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percentage(percent).map(Dimension::Percent)
+        } else {
+            finite(value).filter(|value| *value == 0.0).map(|_| Dimension::Points(0.0))
+        };
+        dimension.ok_or_else(|| self.error(CssErrorKind::InvalidValue, value))
+    }
+
+    fn single_dimension(&self, values: &Values<'a>) -> Result<Dimension, CssError> {
+        self.dimension(self.single(values)?)
+    }
+
+    /// Parses the one to four values of a shorthand like `margin`, in the order top, right,
+    /// bottom and left.
+    fn edges(&self, values: &Values<'a>) -> Result<Rect<Dimension>, CssError> {
+        let mut dimensions = [Dimension::Undefined; 4];
+        for (dimension, value) in dimensions.iter_mut().zip(values) {
+            *dimension = self.dimension(value)?;
+        }
+
+        let [top, right, bottom, left] = match values.len() {
+            1 => [dimensions[0]; 4],
+            2 => [dimensions[0], dimensions[1], dimensions[0], dimensions[1]],
+            3 => [dimensions[0], dimensions[1], dimensions[2], dimensions[1]],
+            _ => dimensions,
+        };
+        Ok(Rect { start: left, end: right, top, bottom })
+    }
+
+    /// Parses the `flex` shorthand, a grow factor and a basis of 0% are used
+    /// when only some of its values are given.
+    fn flex(&self, style: &mut Style, values: &Values<'a>) -> Result<(), CssError> {
+        let (grow, shrink, basis) = match values[..] {
+            [value] if value.eq_ignore_ascii_case("none") => (0.0, 0.0, Dimension::Auto),
+            [value] if value.eq_ignore_ascii_case("auto") => (1.0, 1.0, Dimension::Auto),
+            [value] if value.eq_ignore_ascii_case("initial") => (0.0, 1.0, Dimension::Auto),
+            [value] => match self.number(value) {
+                Ok(grow) => (grow, 1.0, Dimension::Percent(0.0)),
+                Err(_) => (1.0, 1.0, self.dimension(value)?),
+            },
+            [grow, value] => match self.number(value) {
+                Ok(shrink) => (self.number(grow)?, shrink, Dimension::Percent(0.0)),
+                Err(_) => (self.number(grow)?, 1.0, self.dimension(value)?),
+            },
+            [grow, shrink, basis] => (self.number(grow)?, self.number(shrink)?, self.dimension(basis)?),
+            _ => return Err(self.error(CssErrorKind::UnexpectedValue, values[3])),
+        };
+
+        style.flex_grow = grow;
+        style.flex_shrink = shrink;
+        style.flex_basis = basis;
+        Ok(())
+    }
+
+    /// Parses `auto`, a single ratio like `1.5` or a fraction like `16 / 9`.
+    fn aspect_ratio(&self, values: &Values<'a>) -> Result<Number, CssError> {
+        match values[..] {
+            [value] if value.eq_ignore_ascii_case("auto") => Ok(Number::Undefined),
+            [ratio] => Ok(Number::Defined(self.number(ratio)?)),
+            [width, "/", height] => {
+                let ratio = self.number(width)? / self.number(height)?;
+                if ratio.is_finite() {
+                    Ok(Number::Defined(ratio))
+                } else {
+                    Err(self.error(CssErrorKind::InvalidValue, height))
+                }
+            }
+            _ => Err(self.error(CssErrorKind::UnexpectedValue, values[1])),
+        }
+    }
+}
+
+/// Strips the unit from the end of a length, units are case-insensitive like keywords.
+fn strip_unit<'v>(value: &'v str, unit: &str) -> Option<&'v str> {
+    let split = value.len().checked_sub(unit.len())?;
+    if value.get(split..)?.eq_ignore_ascii_case(unit) {
+        Some(&value[..split])
+    } else {
+        None
+    }
+}
+
+/// Parses a number, rejecting the `NaN` and `inf` Rust accepts but CSS does not.
+fn finite(value: &str) -> Option<f32> {
+    value.parse::<f32>().ok().filter(|value| value.is_finite())
+}
+
+/// Parses a percentage as a fraction. Dividing in `f64` keeps written percentages exact.
+fn percentage(value: &str) -> Option<f32> {
+    finite(value)?;
+    value.parse::<f64>().ok().map(|percent| (percent / 100.0) as f32)
+}
+
+impl Style {
+    /// Writes the properties which differ from the default style as CSS declarations,
+    /// which `from_css` parses back into the same style. See the `Display` implementation.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn to_css(&self) -> sys::String {
+        use core::fmt::Write;
+
+        let mut css = sys::String::new();
+        let _ = write!(css, "{}", self);
+        css
+    }
+}
+
+/// Writes a style as CSS, leaving out default properties. Undefined lengths cannot be written
+/// in CSS and are left out as well, they are laid out the same as `auto`.
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let default = Style::default();
+        let mut writer = Writer { f, empty: true };
+
+        macro_rules! keyword {
+            ($property:expr, $field:ident, $($value:path => $keyword:expr),* $(,)?) => {
+                if self.$field != default.$field {
+                    writer.declaration($property, match self.$field { $($value => $keyword),* })?;
+                }
+            };
+        }
+
+        keyword!("display", display, Display::Flex => "flex", Display::None => "none");
+        keyword!("position", position_type,
+            PositionType::Relative => "relative",
+            PositionType::Absolute => "absolute",
+        );
+        keyword!("direction", direction,
+            Direction::Inherit => "inherit",
+            Direction::LTR => "ltr",
+            Direction::RTL => "rtl",
+        );
+        keyword!("flex-direction", flex_direction,
+            FlexDirection::Row => "row",
+            FlexDirection::Column => "column",
+            FlexDirection::RowReverse => "row-reverse",
+            FlexDirection::ColumnReverse => "column-reverse",
+        );
+        keyword!("flex-wrap", flex_wrap,
+            FlexWrap::NoWrap => "nowrap",
+            FlexWrap::Wrap => "wrap",
+            FlexWrap::WrapReverse => "wrap-reverse",
+        );
+        keyword!("overflow", overflow,
+            Overflow::Visible => "visible",
+            Overflow::Hidden => "hidden",
+            Overflow::Scroll => "scroll",
+        );
+        keyword!("align-items", align_items,
+            AlignItems::FlexStart => "flex-start",
+            AlignItems::FlexEnd => "flex-end",
+            AlignItems::Center => "center",
+            AlignItems::Baseline => "baseline",
+            AlignItems::Stretch => "stretch",
+        );
+        keyword!("align-self", align_self,
+            AlignSelf::Auto => "auto",
+            AlignSelf::FlexStart => "flex-start",
+            AlignSelf::FlexEnd => "flex-end",
+            AlignSelf::Center => "center",
+            AlignSelf::Baseline => "baseline",
+            AlignSelf::Stretch => "stretch",
+        );
+        keyword!("align-content", align_content,
+            AlignContent::FlexStart => "flex-start",
+            AlignContent::FlexEnd => "flex-end",
+            AlignContent::Center => "center",
+            AlignContent::Stretch => "stretch",
+            AlignContent::SpaceBetween => "space-between",
+            AlignContent::SpaceAround => "space-around",
+        );
+        keyword!("justify-content", justify_content,
+            JustifyContent::FlexStart => "flex-start",
+            JustifyContent::FlexEnd => "flex-end",
+            JustifyContent::Center => "center",
+            JustifyContent::SpaceBetween => "space-between",
+            JustifyContent::SpaceAround => "space-around",
+            JustifyContent::SpaceEvenly => "space-evenly",
+        );
+
+        writer.edges(self.position, ["inset", "left", "right", "top", "bottom"])?;
+        writer.edges(self.margin, ["margin", "margin-left", "margin-right", "margin-top", "margin-bottom"])?;
+        writer.edges(self.padding, ["padding", "padding-left", "padding-right", "padding-top", "padding-bottom"])?;
+        writer.edges(
+            self.border,
+            ["border-width", "border-left-width", "border-right-width", "border-top-width", "border-bottom-width"],
+        )?;
+
+        if self.flex_grow != default.flex_grow {
+            writer.declaration("flex-grow", self.flex_grow)?;
+        }
+        if self.flex_shrink != default.flex_shrink {
+            writer.declaration("flex-shrink", self.flex_shrink)?;
+        }
+
+        let dimensions = [
+            ("flex-basis", self.flex_basis, default.flex_basis),
+            ("width", self.size.width, default.size.width),
+            ("height", self.size.height, default.size.height),
+            ("min-width", self.min_size.width, default.min_size.width),
+            ("min-height", self.min_size.height, default.min_size.height),
+            ("max-width", self.max_size.width, default.max_size.width),
+            ("max-height", self.max_size.height, default.max_size.height),
+        ];
+        for (property, value, default) in dimensions.iter() {
+            if value != default && *value != Dimension::Undefined {
+                writer.declaration(property, Css(*value))?;
+            }
+        }
+
+        if let Number::Defined(ratio) = self.aspect_ratio {
+            writer.declaration("aspect-ratio", ratio)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes declarations separated by `; `.
+struct Writer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    empty: bool,
+}
+
+impl Writer<'_, '_> {
+    fn declaration(&mut self, property: &str, value: impl fmt::Display) -> fmt::Result {
+        if !self.empty {
+            self.f.write_str("; ")?;
+        }
+        self.empty = false;
+        write!(self.f, "{}: {}", property, value)
+    }
+
+    /// Writes a shorthand if all edges are defined, otherwise the defined edges one by one.
+    /// `properties` holds the shorthand followed by the left, right, top and bottom longhands.
+    fn edges(&mut self, rect: Rect<Dimension>, properties: [&str; 5]) -> fmt::Result {
+        let Rect { start: left, end: right, top, bottom } = rect;
+        let edges = [left, right, top, bottom];
+
+        if edges.iter().all(|edge| *edge != Dimension::Undefined) {
+            let (top, right, bottom, left) = (Css(top), Css(right), Css(bottom), Css(left));
+            return if left != right {
+                self.declaration(properties[0], format_args!("{} {} {} {}", top, right, bottom, left))
+            } else if top != bottom {
+                self.declaration(properties[0], format_args!("{} {} {}", top, right, bottom))
+            } else if top != right {
+                self.declaration(properties[0], format_args!("{} {}", top, right))
+            } else {
+                self.declaration(properties[0], top)
+            };
+        }
+
+        for (property, edge) in properties[1..].iter().zip(edges.iter()) {
+            if *edge != Dimension::Undefined {
+                self.declaration(property, Css(*edge))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a defined dimension as a CSS length.
+#[derive(Copy, Clone, PartialEq)]
+struct Css(Dimension);
+
+impl fmt::Display for Css {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Dimension::Points(points) => write!(f, "{}px", points),
+            Dimension::Percent(fraction) => {
+                use core::fmt::Write;
+
+                // The percentage is exact as an `f64`, but usually has a shorter form which reads
+                // back as the same fraction, e.g. `0.7%` rather than `0.7000000216066837%`.
+                let percent = f64::from(fraction) * 100.0;
+                let mut shortest = ArrayString::<[u8; 64]>::new();
+                for precision in 0..=17 {
+                    shortest.clear();
+                    let _ = write!(shortest, "{:.*}", precision, percent);
+                    if percentage(&shortest) == Some(fraction) {
+                        return write!(f, "{}%", shortest);
+                    }
+                }
+                write!(f, "{}%", percent)
+            }
+            Dimension::Auto | Dimension::Undefined => f.write_str("auto"),
+        }
+    }
+}
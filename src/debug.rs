@@ -0,0 +1,101 @@
+//! Printing of computed layout trees for debugging.
+
+use core::fmt;
+
+use crate::node::{Node, Stretch};
+use crate::style::{Dimension, Display, FlexDirection, FlexWrap, PositionType};
+use crate::Error;
+
+/// Writes the subtree of a node with one line per node, see `Stretch::tree`.
+pub struct TreeDisplay<'a, T> {
+    stretch: &'a Stretch<T>,
+    root: Node,
+}
+
+impl<T> Stretch<T> {
+    /// Returns an adaptor which displays the subtree of `node` as an indented tree. Each line
+    /// shows the node's number, the properties of its style which matter most, its size, its
+    /// location within its parent, its location relative to the root the layout was computed
+    /// from and whether it is dirty.
+    ///
+    /// Use `print_tree` to print to stdout, or `write!` to write to any other `fmt::Write`.
+    pub fn tree(&self, node: Node) -> Result<TreeDisplay<'_, T>, Error> {
+        self.layout(node)?;
+        Ok(TreeDisplay { stretch: self, root: node })
+    }
+
+    /// Prints the subtree of `node` to stdout, see `tree`.
+    #[cfg(feature = "std")]
+    pub fn print_tree(&self, node: Node) -> Result<(), Error> {
+        print!("{}", self.tree(node)?);
+        Ok(())
+    }
+}
+
+impl<T> fmt::Display for TreeDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_node(f, self.root, 0)
+    }
+}
+
+impl<T> TreeDisplay<'_, T> {
+    fn write_node(&self, f: &mut fmt::Formatter, node: Node, depth: usize) -> fmt::Result {
+        // Nodes of the subtree are always valid while the stretch instance is borrowed.
+        let layout = self.stretch.layout(node).unwrap();
+        let style = self.stretch.style(node).unwrap();
+
+        write!(f, "{:indent$}#{}", "", node.number(), indent = depth * 2)?;
+
+        if style.display == Display::None {
+            write!(f, " none")?;
+        }
+        if style.position_type == PositionType::Absolute {
+            write!(f, " absolute")?;
+        }
+        match style.flex_direction {
+            FlexDirection::Row => (),
+            FlexDirection::Column => write!(f, " column")?,
+            FlexDirection::RowReverse => write!(f, " row-reverse")?,
+            FlexDirection::ColumnReverse => write!(f, " column-reverse")?,
+        }
+        match style.flex_wrap {
+            FlexWrap::NoWrap => (),
+            FlexWrap::Wrap => write!(f, " wrap")?,
+            FlexWrap::WrapReverse => write!(f, " wrap-reverse")?,
+        }
+        write_dimension(f, "width", style.size.width)?;
+        write_dimension(f, "height", style.size.height)?;
+        if style.flex_grow != 0.0 {
+            write!(f, " grow={}", style.flex_grow)?;
+        }
+        write_dimension(f, "basis", style.flex_basis)?;
+
+        write!(
+            f,
+            " | size {}x{} at ({}, {}) absolute ({}, {})",
+            layout.size.width,
+            layout.size.height,
+            layout.location.x,
+            layout.location.y,
+            layout.absolute_location.x,
+            layout.absolute_location.y,
+        )?;
+        if self.stretch.dirty(node).unwrap() {
+            write!(f, " dirty")?;
+        }
+        writeln!(f)?;
+
+        for child in self.stretch.children(node).unwrap() {
+            self.write_node(f, child, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_dimension(f: &mut fmt::Formatter, name: &str, dimension: Dimension) -> fmt::Result {
+    match dimension {
+        Dimension::Points(points) => write!(f, " {}={}px", name, points),
+        Dimension::Percent(percent) => write!(f, " {}={}%", name, percent * 100.0),
+        Dimension::Auto | Dimension::Undefined => Ok(()),
+    }
+}
@@ -1,81 +1,509 @@
 //! Forest - ECS like datastructure for storing node trees.
 //!
 //! Backing datastructure for `Stretch` structs.
-use crate::geometry::Size;
+use crate::geometry::{Point, Size};
 use crate::id::NodeId;
-use crate::node::MeasureFunc;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::node::MeasureError;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::node::TreeSpec;
+use crate::node::{MeasureFunc, Node};
 use crate::number::Number;
-use crate::result::{Cache, Layout};
-use crate::style::Style;
+#[cfg(feature = "stats")]
+use crate::result::LayoutStats;
+use crate::result::{Layout, LayoutCache, MeasureCache, Rounding};
+use crate::style::{AlignSelf, Dimension, Display, Overflow, PositionType, Style, StyleChange};
 use crate::sys;
 
+/// Layout state of a node, kept apart from its style and content so the nodes
+/// being laid out stay close together in memory.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct NodeData {
-    pub(crate) style: Style,
-    pub(crate) measure: Option<MeasureFunc>,
-    pub(crate) layout: Layout,
-    pub(crate) layout_cache: Option<Cache>,
+    /// Index of the style of this node in `Forest::styles`.
+    pub(crate) style: usize,
+    /// Layout before rounding, needed to round a subtree again without its ancestors.
+    pub(crate) unrounded_layout: Layout,
+    /// Not serialized, as undefined constraints are stored as NaN. A restored node is measured again.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) layout_cache: LayoutCache,
+    /// Node and parent size of the last final layout pass over this node. Used to lay
+    /// out a dirty subtree again without recomputing its clean ancestors.
+    pub(crate) layout_constraints: Option<(Size<Number>, Size<Number>)>,
     pub(crate) is_dirty: bool,
+    /// Number of times the last layout pass computed this node.
+    #[cfg(feature = "stats")]
+    pub(crate) computations: u32,
 }
 
 impl NodeData {
-    fn new_leaf(style: Style, measure: MeasureFunc) -> Self {
-        Self { style, measure: Some(measure), layout_cache: None, layout: Layout::new(), is_dirty: true }
+    fn new(style: usize) -> Self {
+        Self {
+            style,
+            layout_cache: LayoutCache::default(),
+            layout_constraints: None,
+            unrounded_layout: Layout::new(),
+            is_dirty: true,
+            #[cfg(feature = "stats")]
+            computations: 0,
+        }
+    }
+}
+
+/// Rounded layout of a node, as returned by `Stretch::layout`. Only written when rounding,
+/// so it is kept out of `NodeData`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct RoundedLayout {
+    pub(crate) layout: Layout,
+    /// Whether the last layout hid this node, either by its own `Display::None` or an ancestor's.
+    pub(crate) is_hidden: bool,
+    /// Changes to `layout` not yet reported, see `Forest::changed`.
+    pub(crate) changes: u8,
+}
+
+impl RoundedLayout {
+    pub(crate) const RESIZED: u8 = 1;
+    pub(crate) const MOVED: u8 = 1 << 1;
+    pub(crate) const HIDDEN: u8 = 1 << 2;
+    pub(crate) const SHOWN: u8 = 1 << 3;
+
+    fn new() -> Self {
+        Self { layout: Layout::new(), is_hidden: false, changes: 0 }
+    }
+}
+
+/// The user provided content of a node, only needed to measure leaves.
+pub(crate) struct NodeContent<T> {
+    pub(crate) context: T,
+    pub(crate) measure: Option<MeasureFunc<T>>,
+    /// Handle of the node within the owning `Stretch`, passed to `Measure` implementations.
+    #[cfg_attr(not(any(feature = "std", feature = "alloc")), allow(dead_code))]
+    pub(crate) node: Node,
+    pub(crate) measure_cache: MeasureCache,
+}
+
+/// Neighbours of a node in the list of nodes using the same style entry, see `StyleTable::users`.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct StyleLink {
+    prev: Option<NodeId>,
+    next: Option<NodeId>,
+}
+
+/// Styles of the nodes in a forest, indexed by `NodeData::style`.
+///
+/// Most nodes in a typical tree use the default style, so all of them share a single entry.
+/// Entries are reference counted and reused once no node refers to them anymore.
+#[derive(Clone)]
+#[cfg_attr(all(feature = "serde", any(feature = "std", feature = "alloc")), derive(Serialize, Deserialize))]
+pub(crate) struct StyleTable {
+    styles: sys::Vec<Style>,
+    references: sys::Vec<usize>,
+    /// First node using each entry. The others are found through `Forest::style_links`, so
+    /// updating a shared entry only visits the nodes using it.
+    users: sys::Vec<Option<NodeId>>,
+    free: sys::Vec<usize>,
+    default: Option<usize>,
+}
+
+impl StyleTable {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            styles: sys::new_vec_with_capacity(capacity),
+            references: sys::new_vec_with_capacity(capacity),
+            users: sys::new_vec_with_capacity(capacity),
+            free: sys::new_vec_with_capacity(0),
+            default: None,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, style: Style) -> usize {
+        let is_default = style == Style::default();
+        if let (true, Some(index)) = (is_default, self.default) {
+            self.references[index] += 1;
+            return index;
+        }
+
+        let index = self.register(style);
+        if is_default {
+            self.default = Some(index);
+        }
+        index
+    }
+
+    /// Adds a new entry, even if an equal style is already stored. Nodes given its index can
+    /// later be updated together without affecting any other node.
+    pub(crate) fn register(&mut self, style: Style) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.styles[index] = style;
+                self.references[index] = 1;
+                index
+            }
+            None => {
+                self.styles.push(style);
+                self.references.push(1);
+                self.users.push(None);
+                self.styles.len() - 1
+            }
+        }
+    }
+
+    /// Adds a reference to an existing entry.
+    pub(crate) fn retain(&mut self, index: usize) {
+        self.references[index] += 1;
+    }
+
+    /// Overwrites an entry for every node referring to it.
+    pub(crate) fn set(&mut self, index: usize, style: Style) {
+        self.styles[index] = style;
+    }
+
+    pub(crate) fn release(&mut self, index: usize) {
+        self.references[index] -= 1;
+        if self.references[index] == 0 {
+            self.free.push(index);
+            if self.default == Some(index) {
+                self.default = None;
+            }
+        }
     }
 
-    fn new(style: Style) -> Self {
-        Self { style, measure: None, layout_cache: None, layout: Layout::new(), is_dirty: true }
+    /// Replaces the style at `index` for a single node, returning the new index of its style.
+    pub(crate) fn replace(&mut self, index: usize, style: Style) -> usize {
+        if self.references[index] == 1 && self.default != Some(index) && style != Style::default() {
+            self.styles[index] = style;
+            return index;
+        }
+
+        self.release(index);
+        self.insert(style)
+    }
+}
+
+impl core::ops::Index<usize> for StyleTable {
+    type Output = Style;
+
+    fn index(&self, index: usize) -> &Style {
+        &self.styles[index]
     }
 }
 
-pub(crate) struct Forest {
+/// Everything in a forest except its measure functions, which cannot be copied.
+#[derive(Clone)]
+#[cfg_attr(all(feature = "serde", any(feature = "std", feature = "alloc")), derive(Serialize, Deserialize))]
+pub(crate) struct ForestSnapshot<T> {
+    nodes: sys::Vec<NodeData>,
+    layouts: sys::Vec<RoundedLayout>,
+    style_links: sys::Vec<StyleLink>,
+    contexts: sys::Vec<T>,
+    /// Whether each node had a measure function.
+    measured: sys::Vec<bool>,
+    styles: StyleTable,
+    children: sys::Vec<sys::ChildrenVec<NodeId>>,
+    parents: sys::Vec<sys::ParentsVec<NodeId>>,
+}
+
+pub(crate) struct Forest<T> {
     pub(crate) nodes: sys::Vec<NodeData>,
+    pub(crate) layouts: sys::Vec<RoundedLayout>,
+    pub(crate) style_links: sys::Vec<StyleLink>,
+    pub(crate) contents: sys::Vec<NodeContent<T>>,
+    pub(crate) styles: StyleTable,
     pub(crate) children: sys::Vec<sys::ChildrenVec<NodeId>>,
     pub(crate) parents: sys::Vec<sys::ParentsVec<NodeId>>,
+    /// Number of nodes laid out by the last call to `Forest::compute`.
+    pub(crate) visited_nodes: usize,
+    /// Number of measurements answered by `NodeContent::measure_cache` in the last call to `Forest::compute`.
+    pub(crate) measure_cache_hits: usize,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: LayoutStats,
+    /// Nesting of the computation in progress, see `LayoutStats::max_depth`.
+    #[cfg(feature = "stats")]
+    pub(crate) depth: usize,
+    pub(crate) rounding: Rounding,
+    /// Nodes whose rounded layout changed, in the order they were rounded. Only recorded
+    /// while some caller asked for the changes of a layout pass.
+    pub(crate) changed: Option<sys::Vec<NodeId>>,
+    /// The first failed measurement of the current layout pass.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub(crate) measure_error: Option<(NodeId, MeasureError)>,
 }
 
-impl Forest {
+impl<T> Forest<T> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             nodes: sys::new_vec_with_capacity(capacity),
+            layouts: sys::new_vec_with_capacity(capacity),
+            style_links: sys::new_vec_with_capacity(capacity),
+            contents: sys::new_vec_with_capacity(capacity),
+            styles: StyleTable::with_capacity(capacity),
             children: sys::new_vec_with_capacity(capacity),
             parents: sys::new_vec_with_capacity(capacity),
+            visited_nodes: 0,
+            measure_cache_hits: 0,
+            #[cfg(feature = "stats")]
+            stats: LayoutStats::default(),
+            #[cfg(feature = "stats")]
+            depth: 0,
+            rounding: Rounding::default(),
+            changed: None,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            measure_error: None,
         }
     }
 
-    pub fn new_leaf(&mut self, style: Style, measure: MeasureFunc) -> NodeId {
-        let id = self.nodes.len();
-        self.nodes.push(NodeData::new_leaf(style, measure));
+    pub fn snapshot(&self) -> ForestSnapshot<T>
+    where
+        T: Clone,
+    {
+        ForestSnapshot {
+            nodes: self.nodes.clone(),
+            layouts: self.layouts.clone(),
+            style_links: self.style_links.clone(),
+            contexts: self.contents.iter().map(|content| content.context.clone()).collect(),
+            measured: self.contents.iter().map(|content| content.measure.is_some()).collect(),
+            styles: self.styles.clone(),
+            children: self.children.clone(),
+            parents: self.parents.clone(),
+        }
+    }
+
+    /// Replaces all nodes with those of `snapshot`, whose handles are given by `nodes`. Nodes
+    /// which had a measure function are given the one returned by `measure`.
+    pub fn restore(
+        &mut self,
+        snapshot: ForestSnapshot<T>,
+        nodes: &[Node],
+        mut measure: impl FnMut(Node, &T) -> MeasureFunc<T>,
+    ) {
+        // Moved field by field, destructuring the snapshot first copies it on the stack in debug builds.
+        self.contents = snapshot
+            .contexts
+            .into_iter()
+            .zip(snapshot.measured)
+            .zip(nodes)
+            .map(|((context, measured), &node)| {
+                let measure = if measured { Some(measure(node, &context)) } else { None };
+                NodeContent { context, measure, node, measure_cache: MeasureCache::default() }
+            })
+            .collect();
+        self.nodes = snapshot.nodes;
+        self.layouts = snapshot.layouts;
+        self.style_links = snapshot.style_links;
+        self.styles = snapshot.styles;
+        self.children = snapshot.children;
+        self.parents = snapshot.parents;
+    }
+
+    pub fn new_leaf(&mut self, node: Node, style: Style, measure: MeasureFunc<T>, context: T) -> NodeId {
+        let style = self.styles.insert(style);
+        self.push_leaf(node, style, measure, context)
+    }
+
+    /// Creates a leaf using the style entry at `style`, which is shared with every other node using it.
+    pub fn new_leaf_with_shared_style(
+        &mut self,
+        node: Node,
+        style: usize,
+        measure: MeasureFunc<T>,
+        context: T,
+    ) -> NodeId {
+        self.styles.retain(style);
+        self.push_leaf(node, style, measure, context)
+    }
+
+    fn push_leaf(&mut self, node: Node, style: usize, measure: MeasureFunc<T>, context: T) -> NodeId {
+        let id = self.push_node_data(style);
+        self.contents.push(NodeContent {
+            context,
+            measure: Some(measure),
+            node,
+            measure_cache: MeasureCache::default(),
+        });
         self.children.push(sys::new_vec_with_capacity(0));
         self.parents.push(sys::new_vec_with_capacity(1));
         id
     }
 
-    pub fn new_node(&mut self, style: Style, children: sys::ChildrenVec<NodeId>) -> NodeId {
-        let id = self.nodes.len();
+    pub fn new_node(&mut self, node: Node, style: Style, children: sys::ChildrenVec<NodeId>, context: T) -> NodeId {
+        let style = self.styles.insert(style);
+        self.push_node(node, style, children, context)
+    }
+
+    /// Creates a node using the style entry at `style`, which is shared with every other node using it.
+    pub fn new_node_with_shared_style(
+        &mut self,
+        node: Node,
+        style: usize,
+        children: sys::ChildrenVec<NodeId>,
+        context: T,
+    ) -> NodeId {
+        self.styles.retain(style);
+        self.push_node(node, style, children, context)
+    }
+
+    fn push_node(&mut self, node: Node, style: usize, children: sys::ChildrenVec<NodeId>, context: T) -> NodeId {
+        let id = self.push_node_data(style);
         for child in &children {
             self.parents[*child].push(id);
         }
-        self.nodes.push(NodeData::new(style));
+        self.contents.push(NodeContent { context, measure: None, node, measure_cache: MeasureCache::default() });
         self.children.push(children);
         self.parents.push(sys::new_vec_with_capacity(1));
         id
     }
 
+    /// Adds the layout state of a new node using the style entry at `style`. The caller adds
+    /// its content, children and parents.
+    fn push_node_data(&mut self, style: usize) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(NodeData::new(style));
+        self.layouts.push(RoundedLayout::new());
+        self.style_links.push(StyleLink::default());
+        self.link_style(id);
+        id
+    }
+
+    /// Adds a whole subtree, returning the ids of its nodes in pre-order. Each node is given
+    /// the handle returned by `allocate`.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn build(&mut self, spec: TreeSpec<T>, allocate: &mut impl FnMut() -> Node) -> sys::Vec<NodeId> {
+        let len = spec.node_count();
+        self.nodes.reserve(len);
+        self.layouts.reserve(len);
+        self.style_links.reserve(len);
+        self.contents.reserve(len);
+        self.children.reserve(len);
+        self.parents.reserve(len);
+
+        let mut ids = sys::new_vec_with_capacity(len);
+        self.build_impl(spec, allocate, &mut ids);
+        ids
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn build_impl(
+        &mut self,
+        spec: TreeSpec<T>,
+        allocate: &mut impl FnMut() -> Node,
+        ids: &mut sys::Vec<NodeId>,
+    ) -> NodeId {
+        let TreeSpec { style, measure, context, children } = spec;
+
+        let style = self.styles.insert(style);
+        let id = self.push_node_data(style);
+        ids.push(id);
+        self.contents.push(NodeContent { context, measure, node: allocate(), measure_cache: MeasureCache::default() });
+        self.children.push(sys::new_vec_with_capacity(children.len()));
+        self.parents.push(sys::new_vec_with_capacity(1));
+
+        for child in children {
+            let child = self.build_impl(child, allocate, ids);
+            self.parents[child].push(id);
+            self.children[id].push(child);
+        }
+        id
+    }
+
     pub fn add_child(&mut self, node: NodeId, child: NodeId) {
         self.parents[child].push(node);
         self.children[node].push(child);
         self.mark_dirty(node)
     }
 
+    /// Updates the style of a single node, only invalidating as much layout as the change requires.
+    pub fn set_style(&mut self, node: NodeId, style: Style) {
+        let index = self.nodes[node].style;
+        let change = self.styles[index].change(&style);
+        self.unlink_style(node);
+        self.nodes[node].style = self.styles.replace(index, style);
+        self.link_style(node);
+        self.mark_changed(node, change);
+    }
+
+    /// Makes a node use the shared style entry at `style`.
+    pub fn set_shared_style(&mut self, node: NodeId, style: usize) {
+        let index = self.nodes[node].style;
+        if index == style {
+            return;
+        }
+
+        let change = self.styles[index].change(&self.styles[style]);
+        self.styles.retain(style);
+        self.unlink_style(node);
+        self.styles.release(index);
+        self.nodes[node].style = style;
+        self.link_style(node);
+        self.mark_changed(node, change);
+    }
+
+    /// Updates a shared style entry, invalidating the layout of every node using it.
+    pub fn update_shared_style(&mut self, style: usize, new: Style) {
+        let change = self.styles[style].change(&new);
+        self.styles.set(style, new);
+
+        let mut user = self.styles.users[style];
+        while let Some(node) = user {
+            user = self.style_links[node].next;
+            self.mark_changed(node, change);
+        }
+    }
+
+    /// Adds a node to the users of its style entry.
+    fn link_style(&mut self, node: NodeId) {
+        let style = self.nodes[node].style;
+        let next = self.styles.users[style];
+        self.style_links[node] = StyleLink { prev: None, next };
+        if let Some(next) = next {
+            self.style_links[next].prev = Some(node);
+        }
+        self.styles.users[style] = Some(node);
+    }
+
+    /// Removes a node from the users of its style entry.
+    fn unlink_style(&mut self, node: NodeId) {
+        let StyleLink { prev, next } = self.style_links[node];
+        match prev {
+            Some(prev) => self.style_links[prev].next = next,
+            None => self.styles.users[self.nodes[node].style] = next,
+        }
+        if let Some(next) = next {
+            self.style_links[next].prev = prev;
+        }
+    }
+
+    fn mark_changed(&mut self, node: NodeId, change: StyleChange) {
+        match change {
+            StyleChange::LayoutNeutral => (),
+            StyleChange::Position => self.mark_moved(node),
+            StyleChange::Layout => self.mark_style_dirty(node),
+        }
+    }
+
     pub fn clear(&mut self) {
+        // Registered styles outlive the nodes using them.
+        for node in &self.nodes {
+            self.styles.release(node.style);
+            self.styles.users[node.style] = None;
+        }
         self.nodes.clear();
+        self.layouts.clear();
+        self.style_links.clear();
+        self.contents.clear();
         self.children.clear();
         self.parents.clear();
     }
 
     /// Removes a node and swaps with the last node.
     pub fn swap_remove(&mut self, node: NodeId) -> Option<NodeId> {
+        self.unlink_style(node);
+        self.styles.release(self.nodes[node].style);
         self.nodes.swap_remove(node);
+        self.layouts.swap_remove(node);
+        self.style_links.swap_remove(node);
+        self.contents.swap_remove(node);
 
         // Now the last element is swapped in at index `node`.
         if self.nodes.is_empty() {
@@ -122,6 +550,16 @@ impl Forest {
                 }
             }
 
+            // Update the id of the swapped in node among the users of its style.
+            let StyleLink { prev, next } = self.style_links[node];
+            match prev {
+                Some(prev) => self.style_links[prev].next = Some(node),
+                None => self.styles.users[self.nodes[node].style] = Some(node),
+            }
+            if let Some(next) = next {
+                self.style_links[next].prev = Some(node);
+            }
+
             // Update ids for every parent of the swapped in node.
             for parent in &self.parents[last] {
                 for child in &mut self.children[*parent] {
@@ -154,21 +592,132 @@ impl Forest {
         child
     }
 
-    pub fn mark_dirty(&mut self, node: NodeId) {
-        fn mark_dirty_impl(nodes: &mut sys::Vec<NodeData>, parents: &[sys::ParentsVec<NodeId>], node_id: NodeId) {
-            let node = &mut nodes[node_id];
-            node.layout_cache = None;
-            node.is_dirty = true;
+    /// Whether `node` is `root` or one of its descendants, following the first parent of each node.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn contains(&self, root: NodeId, node: NodeId) -> bool {
+        let mut node = node;
+        while node != root {
+            match self.parents[node].first() {
+                Some(parent) => node = *parent,
+                None => return false,
+            }
+        }
+        true
+    }
 
-            for parent in &parents[node_id] {
-                mark_dirty_impl(nodes, parents, *parent);
+    /// Changes how layouts are rounded. Layouts already computed are rounded again from the
+    /// top of their trees without being laid out again.
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+        for node in 0..self.nodes.len() {
+            if self.parents[node].is_empty() && self.nodes[node].layout_constraints.is_some() {
+                self.round_layout(node, 0.0, 0.0);
             }
         }
+    }
 
-        mark_dirty_impl(&mut self.nodes, &self.parents, node);
+    /// Finds the deepest node below `node` whose layout contains `point`, given relative to the
+    /// root the layout was computed from. Later children are drawn on top of earlier ones and are
+    /// tested first. Children may extend beyond their parent unless it clips its overflow.
+    pub fn hit_test(&self, node: NodeId, point: Point<f32>) -> Option<NodeId> {
+        let style = &self.styles[self.nodes[node].style];
+        if style.display == Display::None {
+            return None;
+        }
+
+        let layout = &self.layouts[node].layout;
+        let inside = point.x >= layout.absolute_location.x
+            && point.x < layout.absolute_location.x + layout.size.width
+            && point.y >= layout.absolute_location.y
+            && point.y < layout.absolute_location.y + layout.size.height;
+
+        if inside || style.overflow == Overflow::Visible {
+            for child in self.children[node].iter().rev() {
+                if let Some(hit) = self.hit_test(*child, point) {
+                    return Some(hit);
+                }
+            }
+        }
+
+        if inside {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    /// Marks a node whose content changed as dirty.
+    ///
+    /// Cached results are cleared up to and including the first relayout boundary, a node
+    /// whose size cannot be affected by changes within its subtree. Ancestors of the boundary
+    /// keep their cached results and are only flagged so layout walks down to the boundary.
+    pub fn mark_dirty(&mut self, node: NodeId) {
+        self.mark_dirty_impl(node, true);
+    }
+
+    /// Marks a node whose own style changed as dirty. Its size may change so every parent
+    /// has to lay out its children again.
+    pub fn mark_style_dirty(&mut self, node: NodeId) {
+        self.nodes[node].layout_cache.clear();
+        self.nodes[node].is_dirty = true;
+
+        for i in 0..self.parents[node].len() {
+            self.mark_dirty_impl(self.parents[node][i], true);
+        }
+    }
+
+    /// Marks a node whose location, but not its size, changed. Only its parents have to
+    /// lay out their children again, the sizes of all other nodes stay the same.
+    pub fn mark_moved(&mut self, node: NodeId) {
+        self.nodes[node].is_dirty = true;
+
+        for i in 0..self.parents[node].len() {
+            let parent = self.parents[node][i];
+            self.nodes[parent].layout_cache.clear();
+            self.mark_dirty_impl(parent, false);
+        }
+    }
+
+    fn mark_dirty_impl(&mut self, node: NodeId, invalidate: bool) {
+        self.nodes[node].is_dirty = true;
+        if invalidate {
+            self.nodes[node].layout_cache.clear();
+        }
+
+        let invalidate = invalidate && !self.is_relayout_boundary(node);
+        for i in 0..self.parents[node].len() {
+            self.mark_dirty_impl(self.parents[node][i], invalidate);
+        }
+    }
+
+    /// Whether the size of a node is fully determined by its own style, meaning nothing
+    /// within its subtree can affect the layout of its ancestors.
+    fn is_relayout_boundary(&self, node: NodeId) -> bool {
+        let style = &self.styles[self.nodes[node].style];
+
+        let fixed_size =
+            matches!(style.size.width, Dimension::Points(_)) && matches!(style.size.height, Dimension::Points(_));
+
+        let inflexible = style.position_type == PositionType::Absolute
+            || (style.flex_grow == 0.0 && style.flex_shrink == 0.0 && style.flex_basis == Dimension::Auto);
+
+        fixed_size && inflexible && !self.affects_baseline(node)
+    }
+
+    /// Whether the baseline of a node is used to align it or one of its ancestors.
+    fn affects_baseline(&self, node: NodeId) -> bool {
+        self.parents[node].iter().any(|parent| {
+            self.styles[self.nodes[node].style].align_self(&self.styles[self.nodes[*parent].style])
+                == AlignSelf::Baseline
+                || (self.children[*parent].first() == Some(&node) && self.affects_baseline(*parent))
+        })
     }
 
     pub fn compute_layout(&mut self, node: NodeId, size: Size<Number>) {
         self.compute(node, size)
     }
+
+    pub fn compute_layouts(&mut self, roots: &[(NodeId, Size<Number>)]) {
+        self.compute_many(roots)
+    }
 }
@@ -24,6 +24,18 @@ impl<T> Rect<T> {
     }
 }
 
+impl Rect<f32> {
+    pub fn zero() -> Self {
+        Self { start: 0.0, end: 0.0, top: 0.0, bottom: 0.0 }
+    }
+}
+
+impl Default for Rect<f32> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
 impl<T> Rect<T>
 where
     T: Add<Output = T> + Copy + Clone,
@@ -153,6 +165,18 @@ impl Size<f32> {
     }
 }
 
+impl Default for Size<f32> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Default for Size<Number> {
+    fn default() -> Self {
+        Size::undefined()
+    }
+}
+
 impl Size<style::Dimension> {
     pub(crate) fn resolve(&self, parent: Size<Number>) -> Size<Number> {
         Size { width: self.width.resolve(parent.width), height: self.height.resolve(parent.height) }
@@ -160,6 +184,9 @@ impl Size<style::Dimension> {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
+#[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
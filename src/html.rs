@@ -0,0 +1,242 @@
+//! Building a tree from the HTML subset used by the fixtures in `test_fixtures`.
+//!
+//! Every element becomes a node styled by its inline `style` attribute. Other attributes
+//! besides `id`, text, declarations like `<!DOCTYPE html>` and everything outside of `<body>` are
+//! ignored. Invalid style declarations are skipped like browsers do, so a fixture is laid out the
+//! same as in the browser.
+
+use std::collections::HashMap;
+
+use crate::css::{self, CssError};
+use crate::node::{Node, Stretch, TreeSpec};
+use crate::style::Style;
+
+/// Elements which never have children or a closing tag.
+const VOID_ELEMENTS: &[&str] = &["area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source"];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HtmlErrorKind {
+    /// The document ended within a tag, comment or element.
+    UnexpectedEnd,
+    /// A closing tag which does not match the element it closes.
+    UnmatchedTag,
+    /// The document has no element to use as root.
+    MissingRoot,
+    /// The document has more than one element at the top level.
+    MultipleRoots,
+}
+
+/// An error in an HTML document. Lines and columns start at 1 and count characters.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct HtmlError {
+    pub kind: HtmlErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for HtmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = match self.kind {
+            HtmlErrorKind::UnexpectedEnd => "Unexpected end of document",
+            HtmlErrorKind::UnmatchedTag => "Unmatched closing tag",
+            HtmlErrorKind::MissingRoot => "Missing root element",
+            HtmlErrorKind::MultipleRoots => "More than one root element",
+        };
+        write!(f, "{} at line {}, column {}", message, self.line, self.column)
+    }
+}
+
+impl std::error::Error for HtmlError {}
+
+/// A tree loaded by `Stretch::load_html`.
+#[derive(Debug)]
+pub struct HtmlTree {
+    pub root: Node,
+    /// Nodes of all elements with an `id` attribute.
+    pub ids: HashMap<String, Node>,
+    /// Style declarations which were skipped, located within the document.
+    pub invalid_declarations: Vec<CssError>,
+}
+
+impl<T: Default> Stretch<T> {
+    /// Adds a node for every element of `html`, see the module documentation.
+    pub fn load_html(&mut self, html: &str) -> Result<HtmlTree, HtmlError> {
+        let body = match html.find("<body") {
+            Some(start) => {
+                let end = tag_end(&html[start..]).ok_or_else(|| error(HtmlErrorKind::UnexpectedEnd, html, ""))?;
+                &html[start + end + 1..]
+            }
+            None => html,
+        };
+
+        let mut parser = Parser { html, rest: body, invalid_declarations: Vec::new() };
+        let mut roots = parser.elements(None)?;
+        let root = match roots.len() {
+            0 => return Err(error(HtmlErrorKind::MissingRoot, html, body)),
+            1 => roots.remove(0),
+            _ => return Err(error(HtmlErrorKind::MultipleRoots, html, roots[1].start)),
+        };
+
+        let mut ids = Vec::new();
+        let (root, nodes) = self.build(root.into_spec(&mut ids));
+        let ids = nodes.into_iter().zip(ids).filter_map(|(node, id)| Some((id?.to_string(), node))).collect();
+        Ok(HtmlTree { root, ids, invalid_declarations: parser.invalid_declarations })
+    }
+}
+
+/// Creates an error located at `at`, a slice of `html`. An empty `at` points to the end.
+fn error(kind: HtmlErrorKind, html: &str, at: &str) -> HtmlError {
+    let at = if at.is_empty() { &html[html.len()..] } else { at };
+    let (line, column) = css::position(html, at);
+    HtmlError { kind, line, column }
+}
+
+/// Finds the `>` closing a tag, skipping those within quoted attribute values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut after_equals = false;
+    for (index, c) in tag.char_indices() {
+        if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+        } else if c == '>' {
+            return Some(index);
+        } else if after_equals && (c == '"' || c == '\'') {
+            quote = Some(c);
+        }
+
+        if !c.is_whitespace() {
+            after_equals = c == '=';
+        }
+    }
+    None
+}
+
+struct Element<'a> {
+    /// The source of the opening tag, used to locate errors.
+    start: &'a str,
+    id: Option<&'a str>,
+    style: Style,
+    children: Vec<Element<'a>>,
+}
+
+impl<'a> Element<'a> {
+    /// Converts the subtree to a spec, pushing the ids of all elements in pre-order.
+    fn into_spec<T: Default>(self, ids: &mut Vec<Option<&'a str>>) -> TreeSpec<T> {
+        ids.push(self.id);
+        TreeSpec::node(self.style, self.children.into_iter().map(|child| child.into_spec(ids)).collect())
+    }
+}
+
+struct Parser<'a> {
+    html: &'a str,
+    /// The part of the document not parsed yet.
+    rest: &'a str,
+    invalid_declarations: Vec<CssError>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: HtmlErrorKind, at: &str) -> HtmlError {
+        error(kind, self.html, at)
+    }
+
+    /// Parses elements until the closing tag of `parent`, or the end of the document or body
+    /// at the top level.
+    fn elements(&mut self, parent: Option<&str>) -> Result<Vec<Element<'a>>, HtmlError> {
+        let mut elements = Vec::new();
+
+        loop {
+            let start = match self.rest.find('<') {
+                Some(start) => start,
+                None if parent.is_none() => return Ok(elements),
+                None => return Err(self.error(HtmlErrorKind::UnexpectedEnd, "")),
+            };
+            self.rest = &self.rest[start..];
+
+            if self.rest.starts_with("<!--") {
+                let end = self.rest.find("-->").ok_or_else(|| self.error(HtmlErrorKind::UnexpectedEnd, ""))?;
+                self.rest = &self.rest[end + 3..];
+            } else if self.rest.starts_with("<!") {
+                // Declarations like `<!DOCTYPE html>` are not elements.
+                let end = self.rest.find('>').ok_or_else(|| self.error(HtmlErrorKind::UnexpectedEnd, ""))?;
+                self.rest = &self.rest[end + 1..];
+            } else if let Some(tag) = self.rest.strip_prefix("</") {
+                let end = tag.find('>').ok_or_else(|| self.error(HtmlErrorKind::UnexpectedEnd, ""))?;
+                let name = tag[..end].trim();
+                match parent {
+                    Some(parent) if name.eq_ignore_ascii_case(parent) => {
+                        self.rest = &tag[end + 1..];
+                        return Ok(elements);
+                    }
+                    None if name.eq_ignore_ascii_case("body") => return Ok(elements),
+                    _ => return Err(self.error(HtmlErrorKind::UnmatchedTag, self.rest)),
+                }
+            } else {
+                elements.push(self.element()?);
+            }
+        }
+    }
+
+    /// Parses an element starting at its opening tag.
+    fn element(&mut self) -> Result<Element<'a>, HtmlError> {
+        let start = self.rest;
+        let tag = &start[1..];
+        let end = tag_end(tag).ok_or_else(|| self.error(HtmlErrorKind::UnexpectedEnd, ""))?;
+        self.rest = &tag[end + 1..];
+
+        let self_closing = tag[..end].ends_with('/');
+        let tag = tag[..end].trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = &tag[..name_end];
+
+        let mut element = Element { start, id: None, style: Style::default(), children: Vec::new() };
+        let mut attributes = &tag[name_end..];
+        while let Some((attribute, value, rest)) = self.attribute(attributes)? {
+            attributes = rest;
+            if attribute.eq_ignore_ascii_case("id") {
+                element.id = Some(value);
+            } else if attribute.eq_ignore_ascii_case("style") {
+                let invalid_declarations = &mut self.invalid_declarations;
+                element.style =
+                    Style::from_css_lenient(self.html, value, &mut |error| invalid_declarations.push(error));
+            }
+        }
+
+        if !self_closing && !VOID_ELEMENTS.iter().any(|void| name.eq_ignore_ascii_case(void)) {
+            element.children = self.elements(Some(name))?;
+        }
+        Ok(element)
+    }
+
+    /// Parses the next attribute of a tag, returning its name, its value and the remaining
+    /// attributes. Attributes without a value have an empty one.
+    #[allow(clippy::type_complexity)]
+    fn attribute(&self, attributes: &'a str) -> Result<Option<(&'a str, &'a str, &'a str)>, HtmlError> {
+        let attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            return Ok(None);
+        }
+
+        let name_end = attributes.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(attributes.len());
+        let name = &attributes[..name_end];
+        let rest = attributes[name_end..].trim_start();
+
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) => rest.trim_start(),
+            None => return Ok(Some((name, &rest[..0], rest))),
+        };
+
+        match rest.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let value = &rest[1..];
+                let end = value.find(quote).ok_or_else(|| self.error(HtmlErrorKind::UnexpectedEnd, ""))?;
+                Ok(Some((name, &value[..end], &value[end + 1..])))
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                Ok(Some((name, &rest[..end], &rest[end..])))
+            }
+        }
+    }
+}
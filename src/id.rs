@@ -8,8 +8,15 @@ pub(crate) type NodeId = usize;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(not(any(feature = "std", feature = "alloc")), derive(hash32_derive::Hash32))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct Id(usize);
 
+impl Id {
+    pub(crate) fn get(self) -> usize {
+        self.0
+    }
+}
+
 pub(crate) struct Allocator {
     new_id: atomic::AtomicUsize,
 }
@@ -23,5 +30,11 @@ impl Allocator {
         Id(self.new_id.fetch_add(1, atomic::Ordering::Relaxed))
     }
 
+    /// Makes sure `id` and the ids before it are not allocated again.
+    pub fn reserve(&mut self, id: Id) {
+        let new_id = self.new_id.get_mut();
+        *new_id = (*new_id).max(id.0 + 1);
+    }
+
     pub fn free(&self, _ids: &[Id]) {}
 }
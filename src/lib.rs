@@ -7,12 +7,20 @@ extern crate alloc;
 #[cfg(feature = "serde")]
 extern crate serde;
 
+pub mod animation;
+#[cfg(feature = "css")]
+pub mod css;
+pub mod debug;
 pub mod geometry;
+#[cfg(feature = "html")]
+pub mod html;
 pub mod node;
 pub mod number;
 pub mod prelude;
 pub mod result;
 pub mod style;
+#[cfg(feature = "svg")]
+pub mod svg;
 
 mod algo;
 mod forest;
@@ -25,6 +33,12 @@ pub use crate::node::Stretch;
 #[derive(Debug)]
 pub enum Error {
     InvalidNode(node::Node),
+    InvalidStyle(node::StyleId),
+    /// The scale factor of `Rounding::Round` is not a positive finite number.
+    InvalidScaleFactor(f32),
+    /// A `Measure` implementation failed to measure the node.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    Measure(node::Node, node::MeasureError),
 }
 
 #[cfg(feature = "std")]
@@ -32,6 +46,9 @@ impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             Error::InvalidNode(ref node) => write!(f, "Invalid node {:?}", node),
+            Error::InvalidStyle(ref style) => write!(f, "Invalid style {:?}", style),
+            Error::InvalidScaleFactor(scale_factor) => write!(f, "Invalid scale factor {}", scale_factor),
+            Error::Measure(ref node, ref error) => write!(f, "Failed to measure node {:?}: {}", node, error),
         }
     }
 }
@@ -41,6 +58,16 @@ impl std::error::Error for Error {
     fn description(&self) -> &str {
         match *self {
             Error::InvalidNode(_) => "The node is not part of the stretch instance",
+            Error::InvalidStyle(_) => "The style is not registered with the stretch instance",
+            Error::InvalidScaleFactor(_) => "The scale factor is not a positive finite number",
+            Error::Measure(..) => "A node could not be measured",
+        }
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Measure(_, ref error) => Some(error.as_ref()),
+            _ => None,
         }
     }
 }
@@ -1,18 +1,165 @@
 use core::ops::Drop;
 
-use crate::forest::Forest;
-use crate::geometry::Size;
+use crate::forest::{Forest, ForestSnapshot, RoundedLayout};
+use crate::geometry::{Point, Size};
 use crate::id::{self, NodeId};
 use crate::number::Number;
-use crate::result::Layout;
+#[cfg(feature = "stats")]
+use crate::result::LayoutStats;
+use crate::result::{Layout, Rounding};
 use crate::style::*;
 use crate::sys;
 use crate::Error;
 
-pub enum MeasureFunc {
-    Raw(fn(Size<Number>) -> Size<f32>),
+/// Measures the intrinsic size of a leaf node given the known constraints.
+///
+/// The measure function is handed the context stored on the node it measures.
+pub enum MeasureFunc<T = ()> {
+    Raw(fn(Size<Number>, &T) -> Size<f32>),
     #[cfg(any(feature = "std", feature = "alloc"))]
-    Boxed(sys::Box<dyn Fn(Size<Number>) -> Size<f32>>),
+    #[allow(clippy::type_complexity)]
+    Boxed(sys::Box<dyn Fn(Size<Number>, &T) -> Size<f32>>),
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    Measure(sys::Box<dyn Measure>),
+    /// The size is not known yet, for example while an image is loading. The leaf is laid out
+    /// with the placeholder size until `Stretch::resolve_measure` provides the actual size.
+    Pending(Size<f32>),
+    /// A fixed intrinsic size, such as the size of a decoded image.
+    Intrinsic(Size<f32>),
+}
+
+/// Space offered to a leaf along one axis, see `Measure`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AvailableSpace {
+    /// The size of the parent is known.
+    Definite(f32),
+    /// The parent is sized by its content, so the leaf may take as much space as it needs.
+    MaxContent,
+}
+
+impl From<Number> for AvailableSpace {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Defined(value) => AvailableSpace::Definite(value),
+            Number::Undefined => AvailableSpace::MaxContent,
+        }
+    }
+}
+
+/// Error of a failed measurement, see `Measure`.
+#[cfg(feature = "std")]
+pub type MeasureError = sys::Box<dyn std::error::Error + Send + Sync>;
+
+/// Error of a failed measurement, see `Measure`. The `Error` trait is only part of `core` in
+/// recent Rust versions, so without std any debuggable error is accepted.
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+pub type MeasureError = sys::Box<dyn core::fmt::Debug + Send + Sync>;
+
+/// Measures leaves which need to know which node they measure, or which may fail.
+///
+/// A node which fails to measure is laid out with an empty size and the first error of the layout
+/// pass is returned by `Stretch::compute_layout` as `Error::Measure`. The failed node is measured
+/// again by the next layout pass.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub trait Measure {
+    /// Returns the size of `node` given its `known` size, which is defined along the axes where
+    /// its style or its parent determine it, and the space `available` within its parent. Results
+    /// are cached, so the available space should only matter along axes where the size is not known.
+    fn measure(
+        &mut self,
+        node: Node,
+        known: Size<Number>,
+        available: Size<AvailableSpace>,
+    ) -> Result<Size<f32>, MeasureError>;
+}
+
+/// Declarative description of a subtree, added to a `Stretch` in one pass with `Stretch::build`.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub struct TreeSpec<T = ()> {
+    pub style: Style,
+    pub measure: Option<MeasureFunc<T>>,
+    pub context: T,
+    pub children: sys::Vec<TreeSpec<T>>,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T: Default> TreeSpec<T> {
+    pub fn node(style: Style, children: sys::Vec<TreeSpec<T>>) -> Self {
+        Self { style, measure: None, context: T::default(), children }
+    }
+
+    pub fn leaf(style: Style, measure: MeasureFunc<T>) -> Self {
+        Self { style, measure: Some(measure), context: T::default(), children: sys::Vec::new() }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<T> TreeSpec<T> {
+    pub fn with_context(self, context: T) -> Self {
+        Self { context, ..self }
+    }
+
+    /// Number of nodes in the subtree.
+    pub fn node_count(&self) -> usize {
+        1 + self.children.iter().map(TreeSpec::node_count).sum::<usize>()
+    }
+}
+
+/// A copy of the nodes, registered styles and layouts of a `Stretch`, see `Stretch::snapshot`.
+///
+/// Measure functions are not part of a snapshot, they are attached again by `Stretch::restore`.
+/// With the `serde` feature a snapshot can be serialized, e.g. to restore a tree in another process.
+#[derive(Clone)]
+#[cfg_attr(all(feature = "serde", any(feature = "std", feature = "alloc")), derive(Serialize, Deserialize))]
+pub struct Snapshot<T = ()> {
+    forest: ForestSnapshot<T>,
+    /// Handle of every node, indexed by its id in the forest.
+    nodes: sys::Vec<Node>,
+    styles: sys::Vec<(StyleId, usize)>,
+}
+
+/// A node and its subtree in a form serde can read and write, see `Stretch::to_document`.
+///
+/// Every field may be left out when loading a document, so a nested tree of styles is enough
+/// to describe a layout.
+#[cfg(all(feature = "serde", any(feature = "std", feature = "alloc")))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
+#[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
+pub struct NodeDocument<T = ()> {
+    #[serde(default)]
+    pub style: Style,
+    #[serde(default)]
+    pub context: T,
+    /// Whether the node had a measure function. Measure functions are rebound by
+    /// `Stretch::load_document`, keyed by the node's context.
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub measure: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    #[serde(default, skip_serializing_if = "sys::Vec::is_empty")]
+    pub children: sys::Vec<NodeDocument<T>>,
+}
+
+/// Nodes whose layout changed in a layout pass, see `Stretch::compute_layout_changes`.
+/// Each list is in tree order.
+#[derive(Debug, Default)]
+pub struct LayoutChanges {
+    /// Nodes whose size changed.
+    pub resized: sys::Vec<Node>,
+    /// Nodes whose location relative to their parent changed.
+    pub moved: sys::Vec<Node>,
+    /// Nodes hidden by their own or an ancestor's `Display::None`.
+    pub hidden: sys::Vec<Node>,
+    /// Nodes hidden before the layout pass but not after it. As hidden nodes have an empty
+    /// layout these are usually reported as resized too.
+    pub shown: sys::Vec<Node>,
+}
+
+impl LayoutChanges {
+    pub fn is_empty(&self) -> bool {
+        self.resized.is_empty() && self.moved.is_empty() && self.hidden.is_empty() && self.shown.is_empty()
+    }
 }
 
 /// Global stretch instance id allocator.
@@ -20,22 +167,42 @@ static INSTANCE_ALLOCATOR: id::Allocator = id::Allocator::new();
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(not(any(feature = "std", feature = "alloc")), derive(hash32_derive::Hash32))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Node {
     instance: id::Id,
     local: id::Id,
 }
 
-pub struct Stretch {
+impl Node {
+    /// Number of the node within its stretch instance, stable for the lifetime of the node.
+    pub(crate) fn number(self) -> usize {
+        self.local.get()
+    }
+}
+
+/// A style registered with `Stretch::register_style`, shared by every node created with it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(not(any(feature = "std", feature = "alloc")), derive(hash32_derive::Hash32))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StyleId {
+    instance: id::Id,
+    local: id::Id,
+}
+
+/// A layout tree whose nodes each carry a user context of type `T`.
+pub struct Stretch<T = ()> {
     id: id::Id,
     nodes: id::Allocator,
     nodes_to_ids: crate::sys::Map<Node, NodeId>,
     ids_to_nodes: crate::sys::Map<NodeId, Node>,
-    forest: Forest,
+    styles: id::Allocator,
+    styles_to_ids: crate::sys::Map<StyleId, usize>,
+    forest: Forest<T>,
 }
 
-impl Default for Stretch {
+impl<T> Default for Stretch<T> {
     fn default() -> Self {
-        Self::with_capacity(16)
+        Self::with_context_capacity(16)
     }
 }
 
@@ -45,11 +212,20 @@ impl Stretch {
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_context_capacity(capacity)
+    }
+}
+
+impl<T> Stretch<T> {
+    /// Creates a stretch instance whose nodes carry a context of type `T`.
+    pub fn with_context_capacity(capacity: usize) -> Self {
         Self {
             id: INSTANCE_ALLOCATOR.allocate(),
             nodes: id::Allocator::new(),
             nodes_to_ids: crate::sys::new_map_with_capacity(capacity),
             ids_to_nodes: crate::sys::new_map_with_capacity(capacity),
+            styles: id::Allocator::new(),
+            styles_to_ids: crate::sys::new_map_with_capacity(0),
             forest: Forest::with_capacity(capacity),
         }
     }
@@ -72,31 +248,244 @@ impl Stretch {
         }
     }
 
-    pub fn new_leaf(&mut self, style: Style, measure: MeasureFunc) -> Result<Node, Error> {
+    // Find the style table entry of a registered style.
+    fn find_style(&self, style: StyleId) -> Result<usize, Error> {
+        match self.styles_to_ids.get(&style) {
+            Some(index) => Ok(*index),
+            None => Err(Error::InvalidStyle(style)),
+        }
+    }
+
+    /// Registers a style that nodes can share. Updating it with `update_style` relayouts
+    /// every node using it.
+    pub fn register_style(&mut self, style: Style) -> StyleId {
+        let id = StyleId { instance: self.id, local: self.styles.allocate() };
+        let index = self.forest.styles.register(style);
+        let _ = self.styles_to_ids.insert(id, index);
+        id
+    }
+
+    /// Replaces a registered style and marks every node using it dirty.
+    pub fn update_style(&mut self, style: StyleId, new: Style) -> Result<(), Error> {
+        let index = self.find_style(style)?;
+        self.forest.update_shared_style(index, new);
+        Ok(())
+    }
+
+    pub fn registered_style(&self, style: StyleId) -> Result<&Style, Error> {
+        let index = self.find_style(style)?;
+        Ok(&self.forest.styles[index])
+    }
+
+    /// Removes a registered style. Nodes using it keep their style, but it can no longer
+    /// be updated or given to new nodes.
+    pub fn unregister_style(&mut self, style: StyleId) -> Result<(), Error> {
+        let index = self.find_style(style)?;
+        self.styles_to_ids.remove(&style);
+        self.styles.free(&[style.local]);
+        self.forest.styles.release(index);
+        Ok(())
+    }
+
+    pub fn new_leaf(&mut self, style: Style, measure: MeasureFunc<T>) -> Result<Node, Error>
+    where
+        T: Default,
+    {
+        self.new_leaf_with_context(style, measure, T::default())
+    }
+
+    pub fn new_leaf_with_context(&mut self, style: Style, measure: MeasureFunc<T>, context: T) -> Result<Node, Error> {
+        let node = self.allocate_node();
+        let id = self.forest.new_leaf(node, style, measure, context);
+        self.add_node(node, id);
+        Ok(node)
+    }
+
+    pub fn new_node(&mut self, style: Style, children: &[Node]) -> Result<Node, Error>
+    where
+        T: Default,
+    {
+        self.new_node_with_context(style, children, T::default())
+    }
+
+    pub fn new_node_with_context(&mut self, style: Style, children: &[Node], context: T) -> Result<Node, Error> {
         let node = self.allocate_node();
-        let id = self.forest.new_leaf(style, measure);
+        let children =
+            children.iter().map(|child| self.find_node(*child)).collect::<Result<sys::ChildrenVec<_>, Error>>()?;
+        let id = self.forest.new_node(node, style, children, context);
         self.add_node(node, id);
         Ok(node)
     }
 
-    pub fn new_node(&mut self, style: Style, children: &[Node]) -> Result<Node, Error> {
+    /// Creates a leaf using a registered style, see `register_style`.
+    pub fn new_leaf_with_style_id(&mut self, style: StyleId, measure: MeasureFunc<T>) -> Result<Node, Error>
+    where
+        T: Default,
+    {
+        self.new_leaf_with_style_id_and_context(style, measure, T::default())
+    }
+
+    pub fn new_leaf_with_style_id_and_context(
+        &mut self,
+        style: StyleId,
+        measure: MeasureFunc<T>,
+        context: T,
+    ) -> Result<Node, Error> {
+        let index = self.find_style(style)?;
+        let node = self.allocate_node();
+        let id = self.forest.new_leaf_with_shared_style(node, index, measure, context);
+        self.add_node(node, id);
+        Ok(node)
+    }
+
+    /// Creates a node using a registered style, see `register_style`.
+    pub fn new_node_with_style_id(&mut self, style: StyleId, children: &[Node]) -> Result<Node, Error>
+    where
+        T: Default,
+    {
+        self.new_node_with_style_id_and_context(style, children, T::default())
+    }
+
+    pub fn new_node_with_style_id_and_context(
+        &mut self,
+        style: StyleId,
+        children: &[Node],
+        context: T,
+    ) -> Result<Node, Error> {
+        let index = self.find_style(style)?;
         let node = self.allocate_node();
         let children =
             children.iter().map(|child| self.find_node(*child)).collect::<Result<sys::ChildrenVec<_>, Error>>()?;
-        let id = self.forest.new_node(style, children);
+        let id = self.forest.new_node_with_shared_style(node, index, children, context);
         self.add_node(node, id);
         Ok(node)
     }
 
+    /// Adds every node of `spec` at once. Returns the root along with the handles of all
+    /// nodes in pre-order, starting with the root.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn build(&mut self, spec: TreeSpec<T>) -> (Node, sys::Vec<Node>) {
+        let (instance, allocator) = (self.id, &self.nodes);
+        let ids = self.forest.build(spec, &mut || Node { instance, local: allocator.allocate() });
+        self.nodes_to_ids.reserve(ids.len());
+        self.ids_to_nodes.reserve(ids.len());
+
+        let nodes: sys::Vec<_> = ids
+            .into_iter()
+            .map(|id| {
+                let node = self.forest.contents[id].node;
+                self.add_node(node, id);
+                node
+            })
+            .collect();
+        (nodes[0], nodes)
+    }
+
+    /// Copies all nodes along with their styles, contexts and layouts.
+    pub fn snapshot(&self) -> Snapshot<T>
+    where
+        T: Clone,
+    {
+        Snapshot {
+            forest: self.forest.snapshot(),
+            nodes: (0..self.forest.nodes.len()).map(|id| self.ids_to_nodes[&id]).collect(),
+            styles: self.styles_to_ids.iter().map(|(style, index)| (*style, *index)).collect(),
+        }
+    }
+
+    /// Replaces all nodes and registered styles with those of `snapshot`. Handles taken before
+    /// the snapshot was made are valid again, and nodes which had a measure function are given
+    /// the one returned by `measure`. Nodes and styles created afterwards never reuse a restored
+    /// handle, even when the snapshot was deserialized from another process.
+    pub fn restore(&mut self, snapshot: Snapshot<T>, mut measure: impl FnMut(Node, &T) -> MeasureFunc<T>) {
+        self.clear();
+        sys::clear_map(&mut self.styles_to_ids);
+
+        // Instance ids are only unique within a process, so restored handles may carry the id of
+        // this instance along with numbers it has not allocated yet.
+        for node in &snapshot.nodes {
+            self.nodes.reserve(node.local);
+        }
+        for (style, _) in &snapshot.styles {
+            self.styles.reserve(style.local);
+        }
+        self.forest.restore(snapshot.forest, &snapshot.nodes, &mut measure);
+
+        for (id, node) in snapshot.nodes.into_iter().enumerate() {
+            self.add_node(node, id);
+        }
+        for (style, index) in snapshot.styles {
+            let _ = self.styles_to_ids.insert(style, index);
+        }
+    }
+
+    /// Describes the subtree of `node`, including the computed layouts if `include_layout` is set.
+    #[cfg(all(feature = "serde", any(feature = "std", feature = "alloc")))]
+    pub fn to_document(&self, node: Node, include_layout: bool) -> Result<NodeDocument<T>, Error>
+    where
+        T: Clone,
+    {
+        let id = self.find_node(node)?;
+        Ok(self.to_document_impl(id, include_layout))
+    }
+
+    #[cfg(all(feature = "serde", any(feature = "std", feature = "alloc")))]
+    fn to_document_impl(&self, id: NodeId, include_layout: bool) -> NodeDocument<T>
+    where
+        T: Clone,
+    {
+        NodeDocument {
+            style: self.forest.styles[self.forest.nodes[id].style],
+            context: self.forest.contents[id].context.clone(),
+            measure: self.forest.contents[id].measure.is_some(),
+            layout: if include_layout { Some(self.forest.layouts[id].layout) } else { None },
+            children: self.forest.children[id]
+                .iter()
+                .map(|child| self.to_document_impl(*child, include_layout))
+                .collect(),
+        }
+    }
+
+    /// Adds the tree described by `document`, see `build`. Nodes which had a measure function
+    /// are given the one returned by `measure`. Layouts in the document are returned by `layout`
+    /// until the tree is computed again.
+    #[cfg(all(feature = "serde", any(feature = "std", feature = "alloc")))]
+    pub fn load_document(
+        &mut self,
+        document: NodeDocument<T>,
+        mut measure: impl FnMut(Node, &T) -> MeasureFunc<T>,
+    ) -> (Node, sys::Vec<Node>) {
+        fn into_spec<T>(document: NodeDocument<T>, nodes: &mut sys::Vec<(bool, Option<Layout>)>) -> TreeSpec<T> {
+            let NodeDocument { style, context, measure, layout, children } = document;
+            nodes.push((measure, layout));
+            let children = children.into_iter().map(|child| into_spec(child, nodes)).collect();
+            TreeSpec { style, measure: None, context, children }
+        }
+
+        let mut documents = sys::Vec::new();
+        let (root, nodes) = self.build(into_spec(document, &mut documents));
+
+        for (node, (measured, layout)) in nodes.iter().zip(documents) {
+            let id = self.nodes_to_ids[node];
+            if measured {
+                self.forest.contents[id].measure = Some(measure(*node, &self.forest.contents[id].context));
+            }
+            if let Some(layout) = layout {
+                self.forest.layouts[id].layout = layout;
+            }
+        }
+        (root, nodes)
+    }
+
     /// Removes all nodes.
     ///
-    /// All associated nodes will be invalid.
+    /// All associated nodes will be invalid, registered styles are kept.
     pub fn clear(&mut self) {
         for node in self.nodes_to_ids.keys() {
             self.nodes.free(&[node.local]);
         }
-        self.nodes_to_ids.clear();
-        self.ids_to_nodes.clear();
+        sys::clear_map(&mut self.nodes_to_ids);
+        sys::clear_map(&mut self.ids_to_nodes);
         self.forest.clear();
     }
 
@@ -114,13 +503,54 @@ impl Stretch {
         }
     }
 
-    pub fn set_measure(&mut self, node: Node, measure: Option<MeasureFunc>) -> Result<(), Error> {
+    pub fn set_measure(&mut self, node: Node, measure: Option<MeasureFunc<T>>) -> Result<(), Error> {
+        let id = self.find_node(node)?;
+        self.forest.contents[id].measure = measure;
+        self.forest.contents[id].measure_cache.clear();
+        self.forest.mark_dirty(id);
+        Ok(())
+    }
+
+    /// Discards the cached results of measuring `node` and marks it dirty. Measurements are
+    /// otherwise only repeated after the measure function or the context of a node changes, so
+    /// this is needed when a measure function depends on state outside of the node, e.g. after
+    /// loading a font.
+    pub fn invalidate_measure(&mut self, node: Node) -> Result<(), Error> {
         let id = self.find_node(node)?;
-        self.forest.nodes[id].measure = measure;
+        self.forest.contents[id].measure_cache.clear();
         self.forest.mark_dirty(id);
         Ok(())
     }
 
+    /// Replaces a pending measurement with the actual size of `node`, see `MeasureFunc::Pending`.
+    /// Only `node` and its ancestors are marked dirty, so the next layout pass only lays out the
+    /// parts of the tree affected by the new size.
+    pub fn resolve_measure(&mut self, node: Node, size: Size<f32>) -> Result<(), Error> {
+        self.set_measure(node, Some(MeasureFunc::Intrinsic(size)))
+    }
+
+    /// Returns the leaves in the tree of `root` still waiting for their size, see
+    /// `MeasureFunc::Pending`. Leaves hidden by the last layout pass are left out.
+    pub fn pending_measures(&self, root: Node) -> Result<sys::Vec<Node>, Error> {
+        let id = self.find_node(root)?;
+        let mut pending = sys::Vec::new();
+        self.collect_pending_measures(id, &mut pending);
+        Ok(pending)
+    }
+
+    fn collect_pending_measures(&self, id: NodeId, pending: &mut sys::Vec<Node>) {
+        if self.forest.layouts[id].is_hidden {
+            return;
+        }
+
+        if let Some(MeasureFunc::Pending(_)) = self.forest.contents[id].measure {
+            pending.push(self.ids_to_nodes[&id]);
+        }
+        for child in &self.forest.children[id] {
+            self.collect_pending_measures(*child, pending);
+        }
+    }
+
     pub fn add_child(&mut self, node: Node, child: Node) -> Result<(), Error> {
         let node_id = self.find_node(node)?;
         let child_id = self.find_node(child)?;
@@ -194,21 +624,88 @@ impl Stretch {
         Ok(self.forest.children[id].len())
     }
 
+    /// Updates the style of a node, only invalidating as much layout as the change requires.
     pub fn set_style(&mut self, node: Node, style: Style) -> Result<(), Error> {
         let id = self.find_node(node)?;
-        self.forest.nodes[id].style = style;
-        self.forest.mark_dirty(id);
+        self.forest.set_style(id, style);
+        Ok(())
+    }
+
+    /// Makes a node use a registered style, see `register_style`.
+    pub fn set_style_id(&mut self, node: Node, style: StyleId) -> Result<(), Error> {
+        let id = self.find_node(node)?;
+        let index = self.find_style(style)?;
+        self.forest.set_shared_style(id, index);
         Ok(())
     }
 
     pub fn style(&self, node: Node) -> Result<&Style, Error> {
         let id = self.find_node(node)?;
-        Ok(&self.forest.nodes[id].style)
+        Ok(&self.forest.styles[self.forest.nodes[id].style])
+    }
+
+    pub fn get_context(&self, node: Node) -> Result<&T, Error> {
+        let id = self.find_node(node)?;
+        Ok(&self.forest.contents[id].context)
+    }
+
+    /// Returns the context of a node for modification and marks the node dirty, as
+    /// the context may change the result of the node's measure function.
+    pub fn get_context_mut(&mut self, node: Node) -> Result<&mut T, Error> {
+        let id = self.find_node(node)?;
+        self.forest.contents[id].measure_cache.clear();
+        self.forest.mark_dirty(id);
+        Ok(&mut self.forest.contents[id].context)
+    }
+
+    /// Replaces the context of a node and marks it dirty, as the context
+    /// may change the result of the node's measure function.
+    pub fn set_context(&mut self, node: Node, context: T) -> Result<(), Error> {
+        let id = self.find_node(node)?;
+        self.forest.contents[id].context = context;
+        self.forest.contents[id].measure_cache.clear();
+        self.forest.mark_dirty(id);
+        Ok(())
     }
 
     pub fn layout(&self, node: Node) -> Result<&Layout, Error> {
         let id = self.find_node(node)?;
-        Ok(&self.forest.nodes[id].layout)
+        Ok(&self.forest.layouts[id].layout)
+    }
+
+    /// Returns the layout of `node` before rounding, see `set_rounding`.
+    pub fn unrounded_layout(&self, node: Node) -> Result<&Layout, Error> {
+        let id = self.find_node(node)?;
+        Ok(&self.forest.nodes[id].unrounded_layout)
+    }
+
+    /// Returns how layouts are rounded, `Rounding::Round(1.0)` by default.
+    pub fn rounding(&self) -> Rounding {
+        self.forest.rounding
+    }
+
+    /// Sets how layouts are rounded. Rounding to device pixels avoids blurry edges on high
+    /// density displays, `Rounding::None` keeps the exact values. Computed layouts are rounded
+    /// again right away. A scale factor which is not a positive finite number is rejected, as
+    /// it would turn every layout into NaN.
+    pub fn set_rounding(&mut self, rounding: Rounding) -> Result<(), Error> {
+        if let Rounding::Round(scale_factor) = rounding {
+            if !(scale_factor.is_finite() && scale_factor > 0.0) {
+                return Err(Error::InvalidScaleFactor(scale_factor));
+            }
+        }
+
+        self.forest.set_rounding(rounding);
+        Ok(())
+    }
+
+    /// Returns the deepest node in the tree of `root` under `point`, which is relative to `root`.
+    /// Nodes with `Display::None` and their children are never hit.
+    pub fn hit_test(&self, root: Node, point: Point<f32>) -> Result<Option<Node>, Error> {
+        let id = self.find_node(root)?;
+        let origin = self.forest.layouts[id].layout.absolute_location;
+        let point = Point { x: origin.x + point.x, y: origin.y + point.y };
+        Ok(self.forest.hit_test(id, point).map(|hit| self.ids_to_nodes[&hit]))
     }
 
     pub fn mark_dirty(&mut self, node: Node) -> Result<(), Error> {
@@ -222,14 +719,119 @@ impl Stretch {
         Ok(self.forest.nodes[id].is_dirty)
     }
 
+    /// Number of nodes laid out by the last call to `compute_layout`. Nodes whose layout
+    /// was still valid are not counted.
+    pub fn visited_nodes(&self) -> usize {
+        self.forest.visited_nodes
+    }
+
+    /// Number of times the last call to `compute_layout` reused the result of a measure function
+    /// instead of calling it, see `invalidate_measure`.
+    pub fn measure_cache_hits(&self) -> usize {
+        self.forest.measure_cache_hits
+    }
+
+    /// Counters describing the work done by the last call to `compute_layout`.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> LayoutStats {
+        LayoutStats { measure_cache_hits: self.forest.measure_cache_hits, ..self.forest.stats }
+    }
+
+    /// Number of times the last call to `compute_layout` laid out or measured `node`. A node
+    /// computed many more times than its parent points to constraints defeating the layout cache.
+    #[cfg(feature = "stats")]
+    pub fn computations(&self, node: Node) -> Result<u32, Error> {
+        let id = self.find_node(node)?;
+        Ok(self.forest.nodes[id].computations)
+    }
+
     pub fn compute_layout(&mut self, node: Node, size: Size<Number>) -> Result<(), Error> {
         let id = self.find_node(node)?;
         self.forest.compute_layout(id, size);
+        self.take_measure_error()
+    }
+
+    /// Same as `compute_layout`, but also returns the nodes whose rounded layout changed.
+    /// Hidden nodes are only reported as hidden, not as resized or moved.
+    pub fn compute_layout_changes(&mut self, node: Node, size: Size<Number>) -> Result<LayoutChanges, Error> {
+        let id = self.find_node(node)?;
+        self.forest.changed = Some(sys::Vec::new());
+        self.forest.compute_layout(id, size);
+        let changes = self.take_changes();
+        self.take_measure_error()?;
+        Ok(changes)
+    }
+
+    fn take_changes(&mut self) -> LayoutChanges {
+        let mut changes = LayoutChanges::default();
+        for id in self.forest.changed.take().unwrap_or_default() {
+            let node = self.ids_to_nodes[&id];
+            let flags = core::mem::replace(&mut self.forest.layouts[id].changes, 0);
+            if flags & RoundedLayout::RESIZED != 0 {
+                changes.resized.push(node);
+            }
+            if flags & RoundedLayout::MOVED != 0 {
+                changes.moved.push(node);
+            }
+            if flags & RoundedLayout::HIDDEN != 0 {
+                changes.hidden.push(node);
+            }
+            if flags & RoundedLayout::SHOWN != 0 {
+                changes.shown.push(node);
+            }
+        }
+        changes
+    }
+
+    /// Lays out several trees in one pass, see `compute_layout`. Returns a result for each
+    /// root in order, invalid roots are skipped without affecting the others.
+    pub fn compute_layouts(&mut self, roots: &[(Node, Size<Number>)]) -> sys::Vec<Result<(), Error>> {
+        let results: sys::Vec<_> = roots.iter().map(|(node, size)| Ok((self.find_node(*node)?, *size))).collect();
+        let roots: sys::Vec<_> = results.iter().filter_map(|result| result.as_ref().ok().copied()).collect();
+        self.forest.compute_layouts(&roots);
+        self.layout_results(results)
+    }
+
+    /// Returns the first failed measurement of the last layout pass as an error. The failed
+    /// node is marked dirty, so it is measured again by the next layout pass.
+    fn take_measure_error(&mut self) -> Result<(), Error> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some((id, error)) = self.forest.measure_error.take() {
+                self.forest.mark_dirty(id);
+                return Err(Error::Measure(self.ids_to_nodes[&id], error));
+            }
+        }
         Ok(())
     }
+
+    /// Results of laying out several roots, where a failed measurement is reported for the
+    /// root whose tree contains the failed node.
+    fn layout_results(
+        &mut self,
+        results: sys::Vec<Result<(NodeId, Size<Number>), Error>>,
+    ) -> sys::Vec<Result<(), Error>> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if let Some(failed) = self.forest.measure_error.as_ref().map(|(id, _)| *id) {
+                let forest = &self.forest;
+                let index = results.iter().position(|result| match result {
+                    Ok((root, _)) => forest.contains(*root, failed),
+                    Err(_) => false,
+                });
+                let error = self.take_measure_error();
+                let mut results: sys::Vec<_> = results.into_iter().map(|result| result.map(|_| ())).collect();
+                if let Some(index) = index {
+                    results[index] = error;
+                }
+                return results;
+            }
+        }
+        results.into_iter().map(|result| result.map(|_| ())).collect()
+    }
 }
 
-impl Drop for Stretch {
+impl<T> Drop for Stretch<T> {
     fn drop(&mut self) {
         INSTANCE_ALLOCATOR.free(&[self.id]);
     }
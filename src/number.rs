@@ -1,11 +1,12 @@
 use core::ops;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum Number {
     Defined(f32),
+    #[default]
     Undefined,
 }
 
@@ -13,12 +14,6 @@ pub trait OrElse<T> {
     fn or_else(self, other: T) -> T;
 }
 
-impl Default for Number {
-    fn default() -> Self {
-        Self::Undefined
-    }
-}
-
 impl OrElse<f32> for Number {
     fn or_else(self, other: f32) -> f32 {
         match self {
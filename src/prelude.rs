@@ -1,8 +1,9 @@
 pub use crate::{
+    animation::LayoutSnapshot,
     geometry::{Rect, Size},
-    node::{Node, Stretch},
+    node::{LayoutChanges, Node, Snapshot, Stretch, StyleId},
     number::Number,
-    result::Layout,
+    result::{Layout, Rounding},
     style::{
         AlignContent, AlignItems, AlignSelf, Dimension, Direction, Display, FlexDirection, FlexWrap, JustifyContent,
         Overflow, PositionType, Style,
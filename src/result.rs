@@ -1,25 +1,319 @@
+use core::f32;
+
 use crate::algo::ComputeResult;
-use crate::geometry::{Point, Size};
-use crate::number::Number;
+use crate::geometry::{Point, Rect, Size};
+use crate::number::{Number, OrElse};
+use crate::sys;
+
+/// Number of cached results of measuring a node, see `LayoutCache::measure_slot` and `MeasureCache`.
+const MEASURE_CACHE_SIZE: usize = 8;
 
 #[derive(Copy, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
+#[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub struct Layout {
     pub(crate) order: u32,
     pub size: Size<f32>,
+    /// Location relative to the parent.
     pub location: Point<f32>,
+    /// Location relative to the root the layout was computed from.
+    pub absolute_location: Point<f32>,
+    /// Margin, border and padding as resolved by the layout algorithm.
+    pub margin: Rect<f32>,
+    pub border: Rect<f32>,
+    pub padding: Rect<f32>,
 }
 
 impl Layout {
     pub(crate) fn new() -> Self {
-        Self { order: 0, size: Size::zero(), location: Point::zero() }
+        Self {
+            order: 0,
+            size: Size::zero(),
+            location: Point::zero(),
+            absolute_location: Point::zero(),
+            margin: Rect::zero(),
+            border: Rect::zero(),
+            padding: Rect::zero(),
+        }
+    }
+
+    /// Linear interpolation towards `to`, equal to `self` at `t = 0.0` and to `to` at `t = 1.0`.
+    /// The order is taken from `to`.
+    pub fn interpolate(&self, to: &Layout, t: f32) -> Layout {
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        let lerp_point = |from: Point<f32>, to: Point<f32>| Point { x: lerp(from.x, to.x), y: lerp(from.y, to.y) };
+        let lerp_rect = |from: Rect<f32>, to: Rect<f32>| Rect {
+            start: lerp(from.start, to.start),
+            end: lerp(from.end, to.end),
+            top: lerp(from.top, to.top),
+            bottom: lerp(from.bottom, to.bottom),
+        };
+
+        Layout {
+            order: to.order,
+            size: Size { width: lerp(self.size.width, to.size.width), height: lerp(self.size.height, to.size.height) },
+            location: lerp_point(self.location, to.location),
+            absolute_location: lerp_point(self.absolute_location, to.absolute_location),
+            margin: lerp_rect(self.margin, to.margin),
+            border: lerp_rect(self.border, to.border),
+            padding: lerp_rect(self.padding, to.padding),
+        }
+    }
+
+    /// Edges of the content box, the area inside border and padding, relative to the parent
+    /// like `location`.
+    pub fn content_box(&self) -> Rect<f32> {
+        Rect {
+            start: self.location.x + self.border.start + self.padding.start,
+            end: self.location.x + self.size.width - self.border.end - self.padding.end,
+            top: self.location.y + self.border.top + self.padding.top,
+            bottom: self.location.y + self.size.height - self.border.bottom - self.padding.bottom,
+        }
+    }
+}
+
+/// Counters describing the work done by the last layout pass, see `Stretch::stats`.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayoutStats {
+    /// Number of times a node was laid out or measured, including the measurements made while
+    /// laying out its parent.
+    pub computations: usize,
+    /// Computations answered by the layout cache of the node.
+    pub cache_hits: usize,
+    /// Computations which missed the layout cache and ran the layout algorithm.
+    pub cache_misses: usize,
+    /// Calls to measure functions and `Measure` implementations. Pending and intrinsic sizes
+    /// are not counted, as no code of the user runs to measure them.
+    pub measure_calls: usize,
+    /// Measurements answered by the measure cache of a leaf, see `Stretch::measure_cache_hits`.
+    pub measure_cache_hits: usize,
+    /// Deepest nesting of computations, with the root at depth 1.
+    pub max_depth: usize,
+}
+
+/// How computed layouts are snapped to the pixel grid, see `Stretch::set_rounding`.
+#[derive(Copy, Clone, Debug)]
+pub enum Rounding {
+    /// Layouts are left unrounded.
+    None,
+    /// Edges are rounded to the nearest device pixel for the given scale factor, so
+    /// `Round(1.0)` rounds to whole points and `Round(3.0)` to thirds of a point.
+    Round(f32),
+    /// Edges are rounded with the given function.
+    Custom(fn(f32) -> f32),
+}
+
+impl Rounding {
+    pub(crate) fn apply(self, value: f32) -> f32 {
+        match self {
+            Rounding::None => value,
+            Rounding::Round(scale_factor) => sys::round(value * scale_factor) / scale_factor,
+            Rounding::Custom(round) => round(value),
+        }
+    }
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::Round(1.0)
     }
 }
 
-#[derive(Debug, Clone)]
+/// A cached result. Constraints are stored as plain floats with `Number::Undefined` as NaN,
+/// which halves the size of an entry as every node carries several of them.
+#[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct Cache {
-    pub(crate) node_size: Size<Number>,
-    pub(crate) parent_size: Size<Number>,
-    pub(crate) perform_layout: bool,
+    node_size: Size<f32>,
+    parent_size: Size<f32>,
+    result: Size<f32>,
+}
+
+impl Cache {
+    fn new(node_size: Size<Number>, parent_size: Size<Number>, result: ComputeResult) -> Self {
+        let pack = |size: Size<Number>| size.map(|value| value.or_else(f32::NAN));
+        Self { node_size: pack(node_size), parent_size: pack(parent_size), result: result.size }
+    }
+
+    /// Whether a measured leaf sized by this result along each axis where `node_size` is defined
+    /// would be measured to the same size again. Such a leaf ignores the size of its parent.
+    fn fits(&self, node_size: Size<Number>) -> bool {
+        let width_compatible = if let Number::Defined(width) = node_size.width {
+            sys::abs(width - self.result.width) < f32::EPSILON
+        } else {
+            self.node_size.width.is_nan()
+        };
+
+        let height_compatible = if let Number::Defined(height) = node_size.height {
+            sys::abs(height - self.result.height) < f32::EPSILON
+        } else {
+            self.node_size.height.is_nan()
+        };
+
+        width_compatible && height_compatible
+    }
+
+    /// Whether the result was computed under exactly these constraints.
+    fn is_for(&self, node_size: Size<Number>, parent_size: Size<Number>) -> bool {
+        let all = Size { width: true, height: true };
+        same(self.node_size, node_size, all) && same(self.parent_size, parent_size, all)
+    }
+}
+
+/// Whether cached constraints are the same as `size` along `axes`.
+fn same(cached: Size<f32>, size: Size<Number>, axes: Size<bool>) -> bool {
+    let same = |cached: f32, value: Number, axis: bool| {
+        !axis
+            || match value {
+                Number::Defined(value) => cached == value,
+                Number::Undefined => cached.is_nan(),
+            }
+    };
+    same(cached.width, size.width, axes.width) && same(cached.height, size.height, axes.height)
+}
+
+/// Results of previous computations of a node, keyed by the constraints they were computed under.
+///
+/// Laying out a flex container computes each child several times under different constraints,
+/// e.g. to find its flex base size, its hypothetical cross size and finally its layout. Keeping
+/// a single result per node would evict them in turn and make deep trees exponentially expensive.
+///
+/// Besides the exact constraints it was computed under, a result is reused under any others the
+/// node would be computed the same under, see `Reuse`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LayoutCache {
+    final_layout: Option<Cache>,
+    /// Only the first `len` measurements are in use, which saves the tag of an `Option` per entry.
+    measurements: [Cache; MEASURE_CACHE_SIZE],
+    len: u8,
+    /// Measurement replaced by the next insertion once all of them are in use.
+    next: u8,
+}
+
+/// How a node depends on the size of its parent, which decides when a cached result is reused.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Reuse {
+    /// A leaf measured from its own size, answered by any result it would be measured to again.
+    /// Percentage margins, borders and padding resolve against the width of the parent.
+    Leaf { percent_edges: bool },
+    /// A leaf measured by a `Measure` implementation, which is also given the space inside its
+    /// parent along each axis where it has no size of its own.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    Measured { percent_edges: bool },
+    /// A container gives its children the space inside its parent along each axis where it has
+    /// no size of its own. Unlike a leaf it is not answered by a result it was sized to, as its
+    /// children would then be given a different size.
+    Container { percent_edges: bool },
+}
+
+impl LayoutCache {
+    pub(crate) fn get(
+        &self,
+        node_size: Size<Number>,
+        parent_size: Size<Number>,
+        perform_layout: bool,
+        reuse: Reuse,
+    ) -> Option<ComputeResult> {
+        let (percent_edges, available_space) = match reuse {
+            Reuse::Leaf { percent_edges } => (percent_edges, false),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            Reuse::Measured { percent_edges } => (percent_edges, true),
+            Reuse::Container { percent_edges } => (percent_edges, true),
+        };
+        let parent_axes = Size {
+            width: percent_edges || (available_space && node_size.width.is_undefined()),
+            height: available_space && node_size.height.is_undefined(),
+        };
+        let reusable = |cache: &Cache| {
+            let node = match reuse {
+                Reuse::Container { .. } => same(cache.node_size, node_size, Size { width: true, height: true }),
+                _ => cache.fits(node_size),
+            };
+            node && same(cache.parent_size, parent_size, parent_axes)
+        };
+
+        let hit = |cache: &Cache| {
+            if cache.is_for(node_size, parent_size) || reusable(cache) {
+                Some(ComputeResult { size: cache.result })
+            } else {
+                None
+            }
+        };
+
+        // A final layout also answers a measurement, but not the other way around.
+        let final_layout = self.final_layout.as_ref().and_then(hit);
+        if perform_layout {
+            final_layout
+        } else {
+            final_layout.or_else(|| self.measurements[..self.len as usize].iter().find_map(hit))
+        }
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        node_size: Size<Number>,
+        parent_size: Size<Number>,
+        perform_layout: bool,
+        result: ComputeResult,
+    ) {
+        let cache = Cache::new(node_size, parent_size, result);
+        if perform_layout {
+            self.final_layout = Some(cache);
+            return;
+        }
+
+        // A result for the same constraints is replaced, otherwise the oldest measurement is
+        // evicted once all slots are in use.
+        let len = self.len as usize;
+        let slot = match self.measurements[..len].iter().position(|entry| entry.is_for(node_size, parent_size)) {
+            Some(slot) => slot,
+            None if len < MEASURE_CACHE_SIZE => {
+                self.len += 1;
+                len
+            }
+            None => {
+                let slot = self.next as usize;
+                self.next = ((slot + 1) % MEASURE_CACHE_SIZE) as u8;
+                slot
+            }
+        };
+        self.measurements[slot] = cache;
+    }
+
+    /// Discards the final layout, keeping the measurements.
+    pub(crate) fn clear_final_layout(&mut self) {
+        self.final_layout = None;
+    }
 
-    pub(crate) result: ComputeResult,
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Results of the measure function of a leaf, keyed by the exact constraints it was called with.
+///
+/// Measuring text is often the most expensive part of a layout, and its inputs rarely change when
+/// the tree around the leaf does. Unlike `LayoutCache` this is therefore not cleared when the leaf
+/// is marked dirty, only when its measure function or context changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MeasureCache {
+    entries: [Option<Cache>; MEASURE_CACHE_SIZE],
+    /// Entry replaced by the next insertion once all entries are in use.
+    next: usize,
+}
+
+impl MeasureCache {
+    pub(crate) fn get(&self, known: Size<Number>, available: Size<Number>) -> Option<Size<f32>> {
+        self.entries.iter().flatten().find(|cache| cache.is_for(known, available)).map(|cache| cache.result)
+    }
+
+    pub(crate) fn insert(&mut self, known: Size<Number>, available: Size<Number>, size: Size<f32>) {
+        self.entries[self.next] = Some(Cache::new(known, available, ComputeResult { size }));
+        self.next = (self.next + 1) % MEASURE_CACHE_SIZE;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
 }
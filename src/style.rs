@@ -1,7 +1,7 @@
 use crate::geometry::{Rect, Size};
 use crate::number::Number;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
@@ -10,20 +10,16 @@ pub enum AlignItems {
     FlexEnd,
     Center,
     Baseline,
+    #[default]
     Stretch,
 }
 
-impl Default for AlignItems {
-    fn default() -> Self {
-        Self::Stretch
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum AlignSelf {
+    #[default]
     Auto,
     FlexStart,
     FlexEnd,
@@ -32,13 +28,7 @@ pub enum AlignSelf {
     Stretch,
 }
 
-impl Default for AlignSelf {
-    fn default() -> Self {
-        Self::Auto
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
@@ -46,22 +36,18 @@ pub enum AlignContent {
     FlexStart,
     FlexEnd,
     Center,
+    #[default]
     Stretch,
     SpaceBetween,
     SpaceAround,
 }
 
-impl Default for AlignContent {
-    fn default() -> Self {
-        Self::Stretch
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum Direction {
+    #[default]
     Inherit,
     #[cfg_attr(feature = "serde", serde(rename = "ltr"))]
     LTR,
@@ -69,77 +55,53 @@ pub enum Direction {
     RTL,
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Self::Inherit
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum Display {
     #[cfg_attr(feature = "serde", serde(rename = "flex"))]
+    #[default]
     Flex,
     #[cfg_attr(feature = "serde", serde(rename = "none"))]
     None,
 }
 
-impl Default for Display {
-    fn default() -> Self {
-        Self::Flex
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum FlexDirection {
+    #[default]
     Row,
     Column,
     RowReverse,
     ColumnReverse,
 }
 
-impl Default for FlexDirection {
-    fn default() -> Self {
-        Self::Row
-    }
-}
-
 impl FlexDirection {
     #[inline]
     pub(crate) fn is_row(self) -> bool {
-        match self {
-            Self::Row | Self::RowReverse => true,
-            _ => false,
-        }
+        matches!(self, Self::Row | Self::RowReverse)
     }
 
     #[inline]
     pub(crate) fn is_column(self) -> bool {
-        match self {
-            Self::Column | Self::ColumnReverse => true,
-            _ => false,
-        }
+        matches!(self, Self::Column | Self::ColumnReverse)
     }
 
     #[inline]
     pub(crate) fn is_reverse(self) -> bool {
-        match self {
-            Self::RowReverse | Self::ColumnReverse => true,
-            _ => false,
-        }
+        matches!(self, Self::RowReverse | Self::ColumnReverse)
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum JustifyContent {
+    #[default]
     FlexStart,
     FlexEnd,
     Center,
@@ -148,76 +110,50 @@ pub enum JustifyContent {
     SpaceEvenly,
 }
 
-impl Default for JustifyContent {
-    fn default() -> Self {
-        Self::FlexStart
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum Overflow {
+    #[default]
     Visible,
     Hidden,
     Scroll,
 }
 
-impl Default for Overflow {
-    fn default() -> Self {
-        Self::Visible
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum PositionType {
+    #[default]
     Relative,
     Absolute,
 }
 
-impl Default for PositionType {
-    fn default() -> Self {
-        Self::Relative
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum FlexWrap {
+    #[default]
     NoWrap,
     Wrap,
     WrapReverse,
 }
 
-impl Default for FlexWrap {
-    fn default() -> Self {
-        Self::NoWrap
-    }
-}
-
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(all(feature = "serde", feature = "serde_kebab_case"), serde(rename_all = "kebab-case"))]
 #[cfg_attr(all(feature = "serde", feature = "serde_camel_case"), serde(rename_all = "camelCase"))]
 pub enum Dimension {
+    #[default]
     Undefined,
     Auto,
     Points(f32),
     Percent(f32),
 }
 
-impl Default for Dimension {
-    fn default() -> Self {
-        Self::Undefined
-    }
-}
-
 impl Dimension {
     pub(crate) fn resolve(self, parent_dim: Number) -> Number {
         match self {
@@ -228,11 +164,7 @@ impl Dimension {
     }
 
     pub(crate) fn is_defined(self) -> bool {
-        match self {
-            Dimension::Points(_) => true,
-            Dimension::Percent(_) => true,
-            _ => false,
-        }
+        matches!(self, Dimension::Points(_) | Dimension::Percent(_))
     }
 }
 
@@ -305,7 +237,36 @@ impl Default for Style {
     }
 }
 
+/// Describes how replacing one style with another affects layout.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum StyleChange {
+    /// Only properties the layout algorithm does not read changed.
+    LayoutNeutral,
+    /// Only the offsets of a relatively positioned node changed. The node keeps
+    /// its size, so only its location within the parent has to be recomputed.
+    Position,
+    /// Anything else, the node and its ancestors have to be laid out again.
+    Layout,
+}
+
 impl Style {
+    pub(crate) fn change(&self, new: &Style) -> StyleChange {
+        // `direction` and `overflow` are not read by the layout algorithm.
+        let neutral = |style: &Style| Style { direction: Default::default(), overflow: Default::default(), ..*style };
+        let (old, new) = (neutral(self), neutral(new));
+
+        if old == new {
+            return StyleChange::LayoutNeutral;
+        }
+
+        let only_moved = Style { position: new.position, ..old } == new;
+        if only_moved && old.position_type == PositionType::Relative {
+            StyleChange::Position
+        } else {
+            StyleChange::Layout
+        }
+    }
+
     pub(crate) fn min_main_size(&self, direction: FlexDirection) -> Dimension {
         if direction.is_row() {
             self.min_size.width
@@ -0,0 +1,115 @@
+//! Rendering of computed layouts to SVG, to inspect them or to compare them in tests.
+
+use std::fmt::Write;
+
+use crate::geometry::{Point, Rect, Size};
+use crate::node::{Node, Stretch};
+use crate::style::{Display, PositionType};
+use crate::Error;
+
+const MARGIN_COLOR: &str = "#f9cc9d";
+const BORDER_COLOR: &str = "#fddd9b";
+const PADDING_COLOR: &str = "#c3d08b";
+const CONTENT_COLOR: &str = "#8cb6c0";
+
+impl<T> Stretch<T> {
+    /// Renders the subtree of `node` as a standalone SVG document. The margin, border, padding
+    /// and content box of every node are filled in their own color and labelled with the
+    /// node's number, absolutely positioned nodes are outlined with a dashed line. Nodes with
+    /// `Display::None` are left out.
+    pub fn to_svg(&self, node: Node) -> Result<String, Error> {
+        let layout = self.layout(node)?;
+        let margin = layout.margin;
+        let bounds = Rect {
+            start: layout.location.x - margin.start,
+            end: layout.location.x + layout.size.width + margin.end,
+            top: layout.location.y - margin.top,
+            bottom: layout.location.y + layout.size.height + margin.bottom,
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            bounds.end - bounds.start,
+            bounds.bottom - bounds.top,
+            bounds.start,
+            bounds.top,
+            bounds.end - bounds.start,
+            bounds.bottom - bounds.top,
+        );
+        self.write_svg(&mut svg, node, Point::zero());
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    fn write_svg(&self, svg: &mut String, node: Node, origin: Point<f32>) {
+        // Nodes of the subtree are always valid while the stretch instance is borrowed.
+        let style = self.style(node).unwrap();
+        if style.display == Display::None {
+            return;
+        }
+
+        let layout = self.layout(node).unwrap();
+        let location = Point { x: origin.x + layout.location.x, y: origin.y + layout.location.y };
+        let border_box = Rect {
+            start: location.x,
+            end: location.x + layout.size.width,
+            top: location.y,
+            bottom: location.y + layout.size.height,
+        };
+        let margin_box = outset(border_box, layout.margin);
+        let padding_box = inset(border_box, layout.border);
+        let content_box = inset(padding_box, layout.padding);
+
+        write_rect(svg, margin_box, MARGIN_COLOR);
+        write_rect(svg, border_box, BORDER_COLOR);
+        write_rect(svg, padding_box, PADDING_COLOR);
+        write_rect(svg, content_box, CONTENT_COLOR);
+        if style.position_type == PositionType::Absolute {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-dasharray="4 2"/>"#,
+                border_box.start,
+                border_box.top,
+                border_box.end - border_box.start,
+                border_box.bottom - border_box.top,
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="monospace" font-size="10" dominant-baseline="hanging">#{}</text>"#,
+            content_box.start,
+            content_box.top,
+            node.number(),
+        );
+
+        for child in self.children(node).unwrap() {
+            self.write_svg(svg, child, location);
+        }
+    }
+}
+
+fn inset(rect: Rect<f32>, edges: Rect<f32>) -> Rect<f32> {
+    Rect {
+        start: rect.start + edges.start,
+        end: rect.end - edges.end,
+        top: rect.top + edges.top,
+        bottom: rect.bottom - edges.bottom,
+    }
+}
+
+fn outset(rect: Rect<f32>, edges: Rect<f32>) -> Rect<f32> {
+    inset(rect, edges.map(|edge| -edge))
+}
+
+fn write_rect(svg: &mut String, rect: Rect<f32>, color: &str) {
+    let size = Size { width: rect.end - rect.start, height: rect.bottom - rect.top };
+    if size.width > 0.0 && size.height > 0.0 {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            rect.start, rect.top, size.width, size.height, color,
+        );
+    }
+}
@@ -2,6 +2,8 @@
 mod std {
     pub type Box<A> = ::std::boxed::Box<A>;
     pub type Map<K, V> = ::std::collections::HashMap<K, V>;
+    #[cfg(feature = "css")]
+    pub type String = ::std::string::String;
     pub type Vec<A> = ::std::vec::Vec<A>;
     pub type ChildrenVec<A> = ::std::vec::Vec<A>;
     pub type ParentsVec<A> = ::std::vec::Vec<A>;
@@ -13,6 +15,10 @@ mod std {
         Map::with_capacity(capacity)
     }
 
+    pub fn clear_map<K, V>(map: &mut Map<K, V>) {
+        map.clear();
+    }
+
     pub fn new_vec_with_capacity<A>(capacity: usize) -> Vec<A> {
         Vec::with_capacity(capacity)
     }
@@ -32,6 +38,8 @@ mod std {
 mod alloc {
     pub type Box<A> = ::alloc::boxed::Box<A>;
     pub type Map<K, V> = ::hashbrown::HashMap<K, V>;
+    #[cfg(feature = "css")]
+    pub type String = ::alloc::string::String;
     pub type Vec<A> = ::alloc::vec::Vec<A>;
     pub type ChildrenVec<A> = ::alloc::vec::Vec<A>;
     pub type ParentsVec<A> = ::alloc::vec::Vec<A>;
@@ -40,6 +48,10 @@ mod alloc {
         Map::with_capacity(capacity)
     }
 
+    pub fn clear_map<K, V>(map: &mut Map<K, V>) {
+        map.clear();
+    }
+
     pub fn new_vec_with_capacity<A>(capacity: usize) -> Vec<A> {
         Vec::with_capacity(capacity)
     }
@@ -75,6 +87,15 @@ mod core {
         Map::new()
     }
 
+    /// Replaces `map` with an empty one, as `IndexMap::clear` of heapless 0.5 indexes past the
+    /// end of its entries.
+    pub fn clear_map<K, V>(map: &mut Map<K, V>)
+    where
+        K: Eq + ::hash32::Hash,
+    {
+        *map = Map::new();
+    }
+
     pub fn new_vec_with_capacity<T, A>(_capacity: usize) -> ::arrayvec::ArrayVec<A>
     where
         A: ::arrayvec::Array<Item = T>,
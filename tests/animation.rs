@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod animation {
+    use stretch::geometry::*;
+    use stretch::style::*;
+    use stretch::Stretch;
+
+    #[test]
+    fn interpolate() {
+        let mut stretch = Stretch::new();
+        let width = |width| Style {
+            size: Size { width: Dimension::Points(width), height: Dimension::Auto },
+            ..Style::default()
+        };
+        let removed = stretch.new_node(width(20.0), &[]).unwrap();
+        let grown = stretch.new_node(Style { flex_grow: 1.0, ..Style::default() }, &[]).unwrap();
+        let hidden = stretch.new_node(Style::default(), &[]).unwrap();
+        let node = stretch.new_node(width(100.0), &[removed, grown, hidden]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        let before = stretch.layout_snapshot(node).unwrap();
+
+        let inserted = stretch.new_node(width(30.0), &[]).unwrap();
+        stretch.set_children(node, &[inserted, grown, hidden]).unwrap();
+        stretch.set_style(hidden, Style { display: Display::None, ..Style::default() }).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        let after = stretch.layout_snapshot(node).unwrap();
+
+        assert_eq!(before.inserted(&after)[..], [inserted]);
+        assert_eq!(before.removed(&after)[..], [removed, hidden]);
+        assert_eq!(after.nodes().collect::<Vec<_>>(), vec![node, inserted, grown]);
+
+        let layouts = before.interpolate(&after, 0.5);
+        assert_eq!(layouts.iter().map(|(node, _)| *node).collect::<Vec<_>>(), vec![node, grown]);
+        assert_eq!(layouts[1].1.location, Point { x: 25.0, y: 0.0 });
+        assert_eq!(layouts[1].1.size.width, 75.0);
+
+        let start = before.get(grown).unwrap();
+        assert_eq!(start.interpolate(after.get(grown).unwrap(), 0.0).location, start.location);
+        assert_eq!(start.interpolate(after.get(grown).unwrap(), 1.0).size, after.get(grown).unwrap().size);
+    }
+}
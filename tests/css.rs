@@ -0,0 +1,176 @@
+#[cfg(feature = "css")]
+mod css {
+    use stretch::css::{CssError, CssErrorKind};
+    use stretch::geometry::*;
+    use stretch::number::Number;
+    use stretch::style::*;
+
+    #[test]
+    fn properties() {
+        let style = Style::from_css(
+            "display: flex; position: absolute; flex-direction: column-reverse; flex-wrap: wrap;
+             align-items: center; align-self: flex-end; justify-content: space-evenly;
+             width: 50%; height: 20px; min-width: 0; max-height: auto; left: -5px; aspect-ratio: 16 / 8;",
+        )
+        .unwrap();
+
+        assert_eq!(style.position_type, PositionType::Absolute);
+        assert_eq!(style.flex_direction, FlexDirection::ColumnReverse);
+        assert_eq!(style.flex_wrap, FlexWrap::Wrap);
+        assert_eq!(style.align_items, AlignItems::Center);
+        assert_eq!(style.align_self, AlignSelf::FlexEnd);
+        assert_eq!(style.justify_content, JustifyContent::SpaceEvenly);
+        assert_eq!(style.size, Size { width: Dimension::Percent(0.5), height: Dimension::Points(20.0) });
+        assert_eq!(style.min_size.width, Dimension::Points(0.0));
+        assert_eq!(style.max_size.height, Dimension::Auto);
+        assert_eq!(style.position.start, Dimension::Points(-5.0));
+        assert_eq!(style.aspect_ratio, Number::Defined(2.0));
+    }
+
+    #[test]
+    fn edge_shorthands() {
+        let style =
+            Style::from_css("margin: 1px; padding: 8px 16px; border-width: 1px 2px 3px; inset: 1px 2px 3px 4px")
+                .unwrap();
+
+        let rect = |start, end, top, bottom| Rect {
+            start: Dimension::Points(start),
+            end: Dimension::Points(end),
+            top: Dimension::Points(top),
+            bottom: Dimension::Points(bottom),
+        };
+        assert_eq!(style.margin, rect(1.0, 1.0, 1.0, 1.0));
+        assert_eq!(style.padding, rect(16.0, 16.0, 8.0, 8.0));
+        assert_eq!(style.border, rect(2.0, 2.0, 1.0, 3.0));
+        assert_eq!(style.position, rect(4.0, 2.0, 1.0, 3.0));
+    }
+
+    #[test]
+    fn flex_shorthand() {
+        let flex = |css| {
+            let style = Style::from_css(css).unwrap();
+            (style.flex_grow, style.flex_shrink, style.flex_basis)
+        };
+
+        assert_eq!(flex("flex: none"), (0.0, 0.0, Dimension::Auto));
+        assert_eq!(flex("flex: auto"), (1.0, 1.0, Dimension::Auto));
+        assert_eq!(flex("flex: 2"), (2.0, 1.0, Dimension::Percent(0.0)));
+        assert_eq!(flex("flex: 10px"), (1.0, 1.0, Dimension::Points(10.0)));
+        assert_eq!(flex("flex: 2 3"), (2.0, 3.0, Dimension::Percent(0.0)));
+        assert_eq!(flex("flex: 2 30%"), (2.0, 1.0, Dimension::Percent(0.3)));
+        assert_eq!(flex("flex: 2 3 auto"), (2.0, 3.0, Dimension::Auto));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |css| Style::from_css(css).unwrap_err();
+
+        assert_eq!(
+            error("width: 10px;\n  colour: red"),
+            CssError { kind: CssErrorKind::UnknownProperty, line: 2, column: 3 }
+        );
+        assert_eq!(error("width: 10em"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 8 });
+        assert_eq!(error("width 10px"), CssError { kind: CssErrorKind::ExpectedColon, line: 1, column: 1 });
+        assert_eq!(error("width:"), CssError { kind: CssErrorKind::ExpectedValue, line: 1, column: 7 });
+        assert_eq!(error("width: 10px 20px"), CssError { kind: CssErrorKind::UnexpectedValue, line: 1, column: 13 });
+        assert_eq!(
+            error("margin: 1px 2px 3px 4px 5px"),
+            CssError { kind: CssErrorKind::UnexpectedValue, line: 1, column: 25 }
+        );
+        assert_eq!(error("display: grid"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 10 });
+        assert_eq!(error("width: NaNpx"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 8 });
+        assert_eq!(error("flex-grow: inf"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 12 });
+        assert_eq!(error("height: 1e40%"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 9 });
+        assert_eq!(error("aspect-ratio: 1 / 0"), CssError { kind: CssErrorKind::InvalidValue, line: 1, column: 19 });
+    }
+
+    #[test]
+    fn case_and_comments() {
+        let style = Style::from_css(
+            "/* header; with a semicolon */ Flex-Direction: column;
+             WIDTH: /* inline */ 10px; /* height: 20px; */ aspect-ratio: 16/*x*//4",
+        )
+        .unwrap();
+
+        assert_eq!(style.flex_direction, FlexDirection::Column);
+        assert_eq!(style.size, Size { width: Dimension::Points(10.0), height: Dimension::Auto });
+        assert_eq!(style.aspect_ratio, Number::Defined(4.0));
+
+        let style =
+            Style::from_css("display: Flex; align-items: CENTER; flex-direction: Row; height: AUTO; width: 10PX")
+                .unwrap();
+        assert_eq!(style.display, Display::Flex);
+        assert_eq!(style.align_items, AlignItems::Center);
+        assert_eq!(style.flex_direction, FlexDirection::Row);
+        assert_eq!(style.size, Size { width: Dimension::Points(10.0), height: Dimension::Auto });
+        assert_eq!(Style::from_css("flex: None").unwrap().flex_grow, 0.0);
+        assert_eq!(Style::from_css("aspect-ratio: Auto").unwrap().aspect_ratio, Number::Undefined);
+
+        assert_eq!(Style::from_css("width: 10px /* unterminated; height: 20px").unwrap().size.height, Dimension::Auto);
+        assert_eq!(
+            Style::from_css("width: 10px; /* a */ colour: red").unwrap_err(),
+            CssError { kind: CssErrorKind::UnknownProperty, line: 1, column: 22 }
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn to_css() {
+        assert_eq!(Style::default().to_css(), "");
+
+        let style = Style {
+            flex_direction: FlexDirection::Column,
+            padding: Rect {
+                start: Dimension::Points(16.0),
+                end: Dimension::Points(16.0),
+                top: Dimension::Points(8.0),
+                bottom: Dimension::Points(8.0),
+            },
+            margin: Rect { top: Dimension::Auto, ..Default::default() },
+            size: Size { width: Dimension::Percent(0.5), height: Dimension::Auto },
+            flex_grow: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            style.to_css(),
+            "flex-direction: column; margin-top: auto; padding: 8px 16px; flex-grow: 1; width: 50%"
+        );
+        assert_eq!(style.to_string(), style.to_css());
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn round_trip() {
+        let css = "display: none; position: absolute; direction: rtl; flex-wrap: wrap-reverse; overflow: hidden; \
+                   align-items: baseline; align-self: stretch; align-content: space-around; \
+                   justify-content: center; inset: 1px 2px 3px; margin-left: -4px; border-width: 1px 2px; \
+                   flex-grow: 2; flex-shrink: 0; flex-basis: 30%; height: 10px; min-width: 5%; max-height: 0px; \
+                   aspect-ratio: 1.5";
+        let style = Style::from_css(css).unwrap();
+        assert_eq!(Style::from_css(&style.to_css()).unwrap(), style);
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn round_trip_arbitrary_lengths() {
+        assert_eq!(Style { flex_basis: Dimension::Percent(0.007), ..Default::default() }.to_css(), "flex-basis: 0.7%");
+
+        let mut seed = 1u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 32) as u32
+        };
+        for _ in 0..10_000 {
+            let bits = f32::from_bits(next());
+            let decimal = (next() % 100_000) as f32 / 1000.0;
+            // Percentages are read as `f32` like other numbers, so they must be finite too.
+            for value in [bits, decimal].iter().copied().filter(|value| (value * 100.0).is_finite()) {
+                let style = Style {
+                    size: Size { width: Dimension::Percent(value), height: Dimension::Points(value) },
+                    ..Default::default()
+                };
+                assert_eq!(Style::from_css(&style.to_css()).unwrap(), style, "{}", style.to_css());
+            }
+        }
+    }
+}
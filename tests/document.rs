@@ -0,0 +1,101 @@
+#[cfg(feature = "serde")]
+mod document {
+    use stretch::geometry::*;
+    use stretch::node::{MeasureFunc, Node, NodeDocument, Snapshot, Stretch, StyleId};
+    use stretch::number::Number;
+    use stretch::style::*;
+
+    // The JSON is written with the default field casing.
+    #[cfg(not(any(feature = "serde_camel_case", feature = "serde_kebab_case")))]
+    #[test]
+    fn load_nested_styles() {
+        let json = r#"{
+            "style": { "flex_direction": "Column" },
+            "children": [
+                { "style": { "size": { "width": { "Points": 10.0 }, "height": { "Points": 20.0 } } } },
+                { "style": { "size": { "width": { "Points": 30.0 }, "height": { "Points": 40.0 } } } }
+            ]
+        }"#;
+
+        let mut stretch = Stretch::new();
+        let document: NodeDocument = serde_json::from_str(json).unwrap();
+        let (root, nodes) = stretch.load_document(document, |_, _| unreachable!());
+        stretch.compute_layout(root, Size::undefined()).unwrap();
+
+        assert_eq!(stretch.layout(root).unwrap().size, Size { width: 30.0, height: 60.0 });
+        assert_eq!(stretch.layout(nodes[2]).unwrap().location, Point { x: 0.0, y: 20.0 });
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut stretch = Stretch::<f32>::default();
+        let measure = |_: Size<Number>, width: &f32| Size { width: *width, height: 10.0 };
+        let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 100.0).unwrap();
+        let node =
+            stretch.new_node(Style { flex_direction: FlexDirection::Column, ..Style::default() }, &[leaf]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let json = serde_json::to_string(&stretch.to_document(node, true).unwrap()).unwrap();
+        let document: NodeDocument<f32> = serde_json::from_str(&json).unwrap();
+        assert!(document.layout.is_some());
+
+        let mut loaded = Stretch::<f32>::default();
+        let (root, nodes) = loaded.load_document(document, |_, _| MeasureFunc::Raw(measure));
+        assert_eq!(loaded.layout(root).unwrap().size, Size { width: 100.0, height: 10.0 });
+        assert_eq!(loaded.style(root).unwrap().flex_direction, FlexDirection::Column);
+
+        loaded.set_context(nodes[1], 50.0).unwrap();
+        loaded.compute_layout(root, Size::undefined()).unwrap();
+        assert_eq!(loaded.layout(root).unwrap().size.width, 50.0);
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut stretch = Stretch::<f32>::default();
+        let measure = |_: Size<Number>, width: &f32| Size { width: *width, height: 10.0 };
+        let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 100.0).unwrap();
+        let node = stretch.new_node(Style::default(), &[leaf]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let json = serde_json::to_string(&(stretch.snapshot(), node, leaf)).unwrap();
+        let (snapshot, node, leaf): (Snapshot<f32>, Node, Node) = serde_json::from_str(&json).unwrap();
+
+        let mut restored = Stretch::<f32>::default();
+        restored.restore(snapshot, |_, _| MeasureFunc::Raw(measure));
+        assert_eq!(restored.children(node).unwrap()[..], [leaf]);
+        assert_eq!(restored.layout(node).unwrap().size, Size { width: 100.0, height: 10.0 });
+        assert!(!restored.dirty(node).unwrap());
+
+        restored.set_context(leaf, 50.0).unwrap();
+        restored.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(restored.layout(node).unwrap().size.width, 50.0);
+    }
+
+    #[test]
+    fn restored_handles_are_not_reused() {
+        let mut stretch = Stretch::<f32>::default();
+        let measure = |_: Size<Number>, width: &f32| Size { width: *width, height: 10.0 };
+        let style = stretch.register_style(Style::default());
+        let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 100.0).unwrap();
+        let node = stretch.new_node_with_style_id(style, &[leaf]).unwrap();
+
+        // Another process numbers its instances from the start again, so the instance a snapshot
+        // is restored into may have the same id as the one it was taken from.
+        let mut restored = Stretch::<f32>::default();
+        let instance = |json: String| serde_json::from_str::<serde_json::Value>(&json).unwrap()["instance"].clone();
+        let from = instance(serde_json::to_string(&node).unwrap());
+        let to = instance(serde_json::to_string(&restored.new_node(Style::default(), &[]).unwrap()).unwrap());
+        let json = serde_json::to_string(&(stretch.snapshot(), node, leaf, style))
+            .unwrap()
+            .replace(&format!(r#""instance":{}"#, from), &format!(r#""instance":{}"#, to));
+        let (snapshot, node, leaf, style): (Snapshot<f32>, Node, Node, StyleId) = serde_json::from_str(&json).unwrap();
+        restored.restore(snapshot, |_, _| MeasureFunc::Raw(measure));
+
+        let new_style = restored.register_style(Style::default());
+        let new_node = restored.new_node(Style::default(), &[]).unwrap();
+        assert!(new_node != node && new_node != leaf);
+        assert!(new_style != style);
+        assert_eq!(restored.children(node).unwrap()[..], [leaf]);
+        assert!(restored.children(new_node).unwrap().is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_update)]
+
 // this declaration is necessary to "mount" the generated code where cargo can see it
 // this allows us to both keep code generation scoped to a singe directory for fs events
 // and to keep each test in a separate file
@@ -0,0 +1,134 @@
+#[cfg(feature = "html")]
+mod html {
+    use stretch::css::{CssError, CssErrorKind};
+    use stretch::geometry::*;
+    use stretch::html::{HtmlError, HtmlErrorKind};
+    use stretch::node::Node;
+    use stretch::Stretch;
+
+    #[test]
+    fn load_fixture() {
+        let html = include_str!("../test_fixtures/absolute_layout_align_items_and_justify_content_center.html");
+        let mut stretch = Stretch::new();
+        let tree = stretch.load_html(html).unwrap();
+        stretch.compute_layout(tree.root, Size::undefined()).unwrap();
+
+        assert_eq!(tree.ids["test-root"], tree.root);
+        assert_eq!(stretch.layout(tree.root).unwrap().size, Size { width: 110.0, height: 100.0 });
+
+        let child = stretch.children(tree.root).unwrap()[0];
+        assert_eq!(stretch.layout(child).unwrap().size, Size { width: 60.0, height: 40.0 });
+        assert_eq!(stretch.layout(child).unwrap().location, Point { x: 25.0, y: 30.0 });
+    }
+
+    /// Layouts asserted by a generated test, in the order of its assertions: a node before its
+    /// children. Each layout is `[width, height, x, y]`.
+    fn expected_layouts(generated: &str) -> Vec<[f32; 4]> {
+        let values: Vec<f32> = generated
+            .lines()
+            .filter(|line| line.trim_start().starts_with("assert_eq!(stretch.layout("))
+            .map(|line| {
+                let value = line.rsplit(", ").next().unwrap().trim_end_matches(");");
+                value.strip_suffix("f32").unwrap().parse().unwrap()
+            })
+            .collect();
+        values.chunks(4).map(|layout| [layout[0], layout[1], layout[2], layout[3]]).collect()
+    }
+
+    fn computed_layouts(stretch: &Stretch, node: Node, layouts: &mut Vec<[f32; 4]>) {
+        let layout = stretch.layout(node).unwrap();
+        layouts.push([layout.size.width, layout.size.height, layout.location.x, layout.location.y]);
+        for child in stretch.children(node).unwrap() {
+            computed_layouts(stretch, child, layouts);
+        }
+    }
+
+    #[test]
+    fn load_all_fixtures() {
+        let root = env!("CARGO_MANIFEST_DIR");
+        for entry in std::fs::read_dir(format!("{}/test_fixtures", root)).unwrap() {
+            let path = entry.unwrap().path();
+            let html = std::fs::read_to_string(&path).unwrap();
+            let mut stretch = Stretch::new();
+            let tree = stretch.load_html(&html).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+            stretch.compute_layout(tree.root, Size::undefined()).unwrap();
+
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            let generated = std::fs::read_to_string(format!("{}/tests/generated/{}.rs", root, name)).unwrap();
+            let mut layouts = Vec::new();
+            computed_layouts(&stretch, tree.root, &mut layouts);
+            assert_eq!(layouts, expected_layouts(&generated), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn ids_and_nesting() {
+        let html = r#"<div id="root" style="flex-direction: column">
+            <div id="a" style="height: 10px"><img id="b" style="height: 5px"></div>
+            <!-- <div id="c"></div> -->
+            <div style="height: 20px" />
+        </div>"#;
+
+        let mut stretch = Stretch::new();
+        let tree = stretch.load_html(html).unwrap();
+        stretch.compute_layout(tree.root, Size::undefined()).unwrap();
+
+        assert_eq!(tree.ids.len(), 3);
+        assert_eq!(stretch.children(tree.root).unwrap().len(), 2);
+        assert_eq!(stretch.children(tree.ids["a"]).unwrap(), vec![tree.ids["b"]]);
+        assert_eq!(stretch.layout(tree.root).unwrap().size.height, 30.0);
+    }
+
+    #[test]
+    fn quoted_angle_bracket() {
+        let html = r#"<div id='root' title="a>b"><div style="height: 10px" data-x='>'></div></div>"#;
+        let mut stretch = Stretch::new();
+        let tree = stretch.load_html(html).unwrap();
+        stretch.compute_layout(tree.root, Size::undefined()).unwrap();
+
+        assert_eq!(tree.ids["root"], tree.root);
+        assert_eq!(stretch.children(tree.root).unwrap().len(), 1);
+        assert_eq!(stretch.layout(tree.root).unwrap().size.height, 10.0);
+    }
+
+    #[test]
+    fn doctype_without_body() {
+        let html = "<!DOCTYPE html>\n<div id=\"root\"><div style=\"height: 10px\"></div></div>";
+        let mut stretch = Stretch::new();
+        let tree = stretch.load_html(html).unwrap();
+        stretch.compute_layout(tree.root, Size::undefined()).unwrap();
+
+        assert_eq!(tree.ids["root"], tree.root);
+        assert_eq!(stretch.layout(tree.root).unwrap().size.height, 10.0);
+    }
+
+    #[test]
+    fn skip_invalid_declarations() {
+        let html = "<div>\n  <div style=\"width: 1em; height: 10px; color: red\"></div>\n</div>";
+        let mut stretch = Stretch::new();
+        let tree = stretch.load_html(html).unwrap();
+
+        let child = stretch.children(tree.root).unwrap()[0];
+        assert_eq!(stretch.style(child).unwrap().size.height, stretch::style::Dimension::Points(10.0));
+        assert_eq!(
+            tree.invalid_declarations,
+            vec![
+                CssError { kind: CssErrorKind::InvalidValue, line: 2, column: 22 },
+                CssError { kind: CssErrorKind::UnknownProperty, line: 2, column: 41 },
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |html| Stretch::new().load_html(html).unwrap_err();
+
+        assert_eq!(error("<div></span>"), HtmlError { kind: HtmlErrorKind::UnmatchedTag, line: 1, column: 6 });
+        assert_eq!(error("<div><div></div>"), HtmlError { kind: HtmlErrorKind::UnexpectedEnd, line: 1, column: 17 });
+        assert_eq!(
+            error("<div></div><div></div>"),
+            HtmlError { kind: HtmlErrorKind::MultipleRoots, line: 1, column: 12 }
+        );
+        assert_eq!(error("<body></body>"), HtmlError { kind: HtmlErrorKind::MissingRoot, line: 1, column: 7 });
+    }
+}
@@ -1,6 +1,13 @@
 #[cfg(test)]
 mod measure {
+    #[cfg(feature = "std")]
+    use std::cell::RefCell;
+    #[cfg(feature = "std")]
+    use std::rc::Rc;
+
     use stretch::node::MeasureFunc;
+    #[cfg(feature = "std")]
+    use stretch::node::{AvailableSpace, Measure, MeasureError};
     use stretch::number::OrElse;
 
     #[test]
@@ -9,7 +16,7 @@ mod measure {
         let node = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -29,7 +36,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -52,7 +59,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -87,7 +94,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -140,7 +147,7 @@ mod measure {
         let child1 = stretch
             .new_leaf(
                 stretch::style::Style { flex_grow: 1.0, ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(10.0),
                     height: constraint.height.or_else(50.0),
                 }),
@@ -186,7 +193,7 @@ mod measure {
         let child1 = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(50.0),
                 }),
@@ -231,7 +238,7 @@ mod measure {
         let child1 = stretch
             .new_leaf(
                 stretch::style::Style { flex_grow: 1.0, ..Default::default() },
-                MeasureFunc::Raw(|constraint| {
+                MeasureFunc::Raw(|constraint, _| {
                     let width = constraint.width.or_else(10.0);
                     let height = constraint.height.or_else(width * 2.0);
                     stretch::geometry::Size { width, height }
@@ -280,7 +287,7 @@ mod measure {
         let child1 = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| {
+                MeasureFunc::Raw(|constraint, _| {
                     let width = constraint.width.or_else(100.0);
                     let height = constraint.height.or_else(width * 2.0);
                     stretch::geometry::Size { width, height }
@@ -315,7 +322,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| {
+                MeasureFunc::Raw(|constraint, _| {
                     let height = constraint.height.or_else(50.0);
                     let width = constraint.width.or_else(height);
                     stretch::geometry::Size { width, height }
@@ -354,7 +361,7 @@ mod measure {
                     },
                     ..Default::default()
                 },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -380,7 +387,7 @@ mod measure {
                     },
                     ..Default::default()
                 },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -415,7 +422,7 @@ mod measure {
                     flex_grow: 1.0,
                     ..Default::default()
                 },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(100.0),
                     height: constraint.height.or_else(100.0),
                 }),
@@ -449,7 +456,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(50.0),
                     height: constraint.height.or_else(50.0),
                 }),
@@ -481,7 +488,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { position_type: stretch::style::PositionType::Absolute, ..Default::default() },
-                MeasureFunc::Raw(|constraint| stretch::geometry::Size {
+                MeasureFunc::Raw(|constraint, _| stretch::geometry::Size {
                     width: constraint.width.or_else(50.0),
                     height: constraint.height.or_else(50.0),
                 }),
@@ -513,7 +520,7 @@ mod measure {
         let child = stretch
             .new_leaf(
                 stretch::style::Style { flex_grow: 1.0, ..Default::default() },
-                MeasureFunc::Raw(|_| stretch::geometry::Size { width: 200.0, height: 200.0 }),
+                MeasureFunc::Raw(|_, _| stretch::geometry::Size { width: 200.0, height: 200.0 }),
             )
             .unwrap();
 
@@ -546,7 +553,7 @@ mod measure {
         let grandchild = stretch
             .new_leaf(
                 stretch::style::Style { ..Default::default() },
-                MeasureFunc::Raw(|constraint| {
+                MeasureFunc::Raw(|constraint, _| {
                     NUM_MEASURES.fetch_add(1, atomic::Ordering::Relaxed);
                     stretch::geometry::Size {
                         width: constraint.width.or_else(50.0),
@@ -563,4 +570,282 @@ mod measure {
 
         assert_eq!(NUM_MEASURES.load(atomic::Ordering::Relaxed), 1);
     }
+
+    #[test]
+    fn measure_cache() {
+        use std::sync::atomic;
+
+        let mut stretch = stretch::node::Stretch::new();
+        static NUM_MEASURES: atomic::AtomicU32 = atomic::AtomicU32::new(0);
+
+        let leaf = stretch
+            .new_leaf(
+                stretch::style::Style { ..Default::default() },
+                MeasureFunc::Raw(|constraint, _| {
+                    NUM_MEASURES.fetch_add(1, atomic::Ordering::Relaxed);
+                    stretch::geometry::Size {
+                        width: constraint.width.or_else(50.0),
+                        height: constraint.height.or_else(50.0),
+                    }
+                }),
+            )
+            .unwrap();
+        let node = stretch.new_node(stretch::style::Style { ..Default::default() }, &[leaf]).unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        let measures = NUM_MEASURES.load(atomic::Ordering::Relaxed);
+        assert_eq!(stretch.measure_cache_hits(), 0);
+
+        // Marking the leaf dirty lays it out again, but its measurements are reused.
+        stretch.mark_dirty(leaf).unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(NUM_MEASURES.load(atomic::Ordering::Relaxed), measures);
+        assert!(stretch.measure_cache_hits() > 0);
+
+        stretch.invalidate_measure(leaf).unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(NUM_MEASURES.load(atomic::Ordering::Relaxed), 2 * measures);
+        assert_eq!(stretch.measure_cache_hits(), 0);
+    }
+
+    #[test]
+    fn measure_with_context() {
+        let mut stretch = stretch::node::Stretch::<&str>::default();
+        let node = stretch
+            .new_leaf_with_context(
+                stretch::style::Style { ..Default::default() },
+                MeasureFunc::Raw(|constraint, text| stretch::geometry::Size {
+                    width: constraint.width.or_else(10.0 * text.len() as f32),
+                    height: constraint.height.or_else(10.0),
+                }),
+                "hello",
+            )
+            .unwrap();
+
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(node).unwrap().size.width, 50.0);
+
+        stretch.set_context(node, "hello world").unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(node).unwrap().size.width, 110.0);
+
+        *stretch.get_context_mut(node).unwrap() = "hi";
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(node).unwrap().size.width, 20.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    struct MissingFont;
+
+    #[cfg(feature = "std")]
+    impl std::fmt::Display for MissingFont {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "missing font")
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for MissingFont {}
+
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct TextEngine {
+        font_loaded: bool,
+        measured: Vec<(stretch::node::Node, stretch::geometry::Size<AvailableSpace>)>,
+    }
+
+    #[cfg(feature = "std")]
+    struct TextMeasure(Rc<RefCell<TextEngine>>);
+
+    #[cfg(feature = "std")]
+    impl Measure for TextMeasure {
+        fn measure(
+            &mut self,
+            node: stretch::node::Node,
+            known: stretch::geometry::Size<stretch::number::Number>,
+            available: stretch::geometry::Size<AvailableSpace>,
+        ) -> Result<stretch::geometry::Size<f32>, MeasureError> {
+            let mut engine = self.0.borrow_mut();
+            if !engine.font_loaded {
+                return Err(MissingFont.into());
+            }
+            engine.measured.push((node, available));
+            Ok(stretch::geometry::Size { width: known.width.or_else(40.0), height: known.height.or_else(10.0) })
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measure_trait() {
+        let engine = Rc::new(RefCell::new(TextEngine { font_loaded: true, ..TextEngine::default() }));
+        let mut stretch = stretch::node::Stretch::new();
+        let child = stretch
+            .new_leaf(stretch::style::Style::default(), MeasureFunc::Measure(Box::new(TextMeasure(engine.clone()))))
+            .unwrap();
+        let node = stretch
+            .new_node(
+                stretch::style::Style {
+                    size: stretch::geometry::Size {
+                        width: stretch::style::Dimension::Points(200.0),
+                        height: stretch::style::Dimension::Auto,
+                    },
+                    ..Default::default()
+                },
+                &[child],
+            )
+            .unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+
+        assert_eq!(stretch.layout(child).unwrap().size.width, 40.0);
+        let (measured, available) = *engine.borrow().measured.last().unwrap();
+        assert_eq!(measured, child);
+        assert_eq!(available.width, AvailableSpace::Definite(200.0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measure_trait_available_space() {
+        let engine = Rc::new(RefCell::new(TextEngine { font_loaded: true, ..TextEngine::default() }));
+        let mut stretch = stretch::node::Stretch::new();
+        let child = stretch
+            .new_leaf(
+                stretch::style::Style {
+                    margin: stretch::geometry::Rect {
+                        start: stretch::style::Dimension::Points(5.0),
+                        end: stretch::style::Dimension::Points(5.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                MeasureFunc::Measure(Box::new(TextMeasure(engine.clone()))),
+            )
+            .unwrap();
+        let node = stretch
+            .new_node(
+                stretch::style::Style {
+                    size: stretch::geometry::Size {
+                        width: stretch::style::Dimension::Points(200.0),
+                        height: stretch::style::Dimension::Points(50.0),
+                    },
+                    padding: stretch::geometry::Rect {
+                        start: stretch::style::Dimension::Points(10.0),
+                        end: stretch::style::Dimension::Points(10.0),
+                        top: stretch::style::Dimension::Points(10.0),
+                        bottom: stretch::style::Dimension::Points(10.0),
+                    },
+                    align_items: stretch::style::AlignItems::FlexStart,
+                    ..Default::default()
+                },
+                &[child],
+            )
+            .unwrap();
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+
+        // The space inside the padding of the parent, less the margins of the leaf.
+        let engine = engine.borrow();
+        assert!(!engine.measured.is_empty());
+        for (_, available) in &engine.measured {
+            assert_eq!(available.width, AvailableSpace::Definite(170.0));
+            assert_eq!(available.height, AvailableSpace::Definite(30.0));
+        }
+    }
+
+    /// Text 150 wide which wraps onto lines 10 high within the available width.
+    #[cfg(feature = "std")]
+    struct WrappingText;
+
+    #[cfg(feature = "std")]
+    impl Measure for WrappingText {
+        fn measure(
+            &mut self,
+            _: stretch::node::Node,
+            known: stretch::geometry::Size<stretch::number::Number>,
+            available: stretch::geometry::Size<AvailableSpace>,
+        ) -> Result<stretch::geometry::Size<f32>, MeasureError> {
+            let width = match available.width {
+                AvailableSpace::Definite(available) => known.width.or_else(available.min(150.0)),
+                _ => known.width.or_else(150.0),
+            };
+            let lines = (150.0 / width).ceil();
+            Ok(stretch::geometry::Size { width, height: known.height.or_else(10.0 * lines) })
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measure_trait_remeasures_in_less_space() {
+        let mut stretch = stretch::node::Stretch::new();
+        let child =
+            stretch.new_leaf(stretch::style::Style::default(), MeasureFunc::Measure(Box::new(WrappingText))).unwrap();
+        let node = stretch.new_node(stretch::style::Style::default(), &[child]).unwrap();
+
+        let width = |width| stretch::geometry::Size {
+            width: stretch::number::Number::Defined(width),
+            height: stretch::number::Number::Undefined,
+        };
+        stretch.compute_layout(node, width(100.0)).unwrap();
+        assert_eq!(stretch.layout(child).unwrap().size, stretch::geometry::Size { width: 100.0, height: 20.0 });
+
+        // The leaf is measured again as its parent gives it less space.
+        stretch.compute_layout(node, width(50.0)).unwrap();
+        assert_eq!(stretch.layout(child).unwrap().size, stretch::geometry::Size { width: 50.0, height: 30.0 });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measure_error() {
+        let engine = Rc::new(RefCell::new(TextEngine::default()));
+        let mut stretch = stretch::node::Stretch::new();
+        let child = stretch
+            .new_leaf(stretch::style::Style::default(), MeasureFunc::Measure(Box::new(TextMeasure(engine.clone()))))
+            .unwrap();
+        let node = stretch.new_node(stretch::style::Style::default(), &[child]).unwrap();
+
+        match stretch.compute_layout(node, stretch::geometry::Size::undefined()) {
+            Err(stretch::Error::Measure(failed, error)) => {
+                assert_eq!(failed, child);
+                assert_eq!(error.to_string(), "missing font");
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(stretch.layout(child).unwrap().size.width, 0.0);
+
+        // The failed node is measured again once the font is available.
+        engine.borrow_mut().font_loaded = true;
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(child).unwrap().size.width, 40.0);
+
+        engine.borrow_mut().font_loaded = false;
+        stretch.invalidate_measure(child).unwrap();
+        let results = stretch.compute_layouts(&[(child, stretch::geometry::Size::undefined())]);
+        assert!(matches!(results[..], [Err(stretch::Error::Measure(failed, _))] if failed == child));
+    }
+
+    #[test]
+    fn pending_measure() {
+        let mut stretch = stretch::node::Stretch::new();
+        let placeholder = stretch::geometry::Size { width: 10.0, height: 10.0 };
+        let image = stretch.new_leaf(stretch::style::Style::default(), MeasureFunc::Pending(placeholder)).unwrap();
+        let hidden = stretch
+            .new_leaf(
+                stretch::style::Style { display: stretch::style::Display::None, ..Default::default() },
+                MeasureFunc::Pending(placeholder),
+            )
+            .unwrap();
+        let text = stretch.new_node(stretch::style::Style::default(), &[]).unwrap();
+        let node = stretch.new_node(stretch::style::Style::default(), &[image, hidden, text]).unwrap();
+
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(image).unwrap().size, placeholder);
+        assert_eq!(stretch.pending_measures(node).unwrap()[..], [image]);
+
+        stretch.resolve_measure(image, stretch::geometry::Size { width: 100.0, height: 50.0 }).unwrap();
+        assert!(stretch.dirty(image).unwrap() && stretch.dirty(node).unwrap());
+        assert!(!stretch.dirty(text).unwrap());
+
+        stretch.compute_layout(node, stretch::geometry::Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(image).unwrap().size, stretch::geometry::Size { width: 100.0, height: 50.0 });
+        assert_eq!(stretch.layout(text).unwrap().location.x, 100.0);
+        assert!(stretch.pending_measures(node).unwrap().is_empty());
+    }
 }
@@ -1,7 +1,13 @@
+#![allow(clippy::bool_assert_comparison)]
+
 #[cfg(test)]
 mod node {
     use stretch::geometry::*;
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    use stretch::node::TreeSpec;
     use stretch::node::{MeasureFunc, Stretch};
+    use stretch::number::Number;
+    use stretch::result::Rounding;
     use stretch::style::*;
 
     #[test]
@@ -20,15 +26,39 @@ mod node {
     fn set_measure() {
         let mut stretch = Stretch::new();
         let node =
-            stretch.new_leaf(Style::default(), MeasureFunc::Raw(|_| Size { width: 200.0, height: 200.0 })).unwrap();
+            stretch.new_leaf(Style::default(), MeasureFunc::Raw(|_, _| Size { width: 200.0, height: 200.0 })).unwrap();
         stretch.compute_layout(node, Size::undefined()).unwrap();
         assert_eq!(stretch.layout(node).unwrap().size.width, 200.0);
 
-        stretch.set_measure(node, Some(MeasureFunc::Raw(|_| Size { width: 100.0, height: 100.0 }))).unwrap();
+        stretch.set_measure(node, Some(MeasureFunc::Raw(|_, _| Size { width: 100.0, height: 100.0 }))).unwrap();
         stretch.compute_layout(node, Size::undefined()).unwrap();
         assert_eq!(stretch.layout(node).unwrap().size.width, 100.0);
     }
 
+    #[test]
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn build() {
+        let mut stretch = Stretch::<u32>::default();
+        let spec = TreeSpec::node(
+            Style::default(),
+            vec![
+                TreeSpec::node(Style::default(), vec![TreeSpec::node(Style::default(), vec![])]).with_context(1),
+                TreeSpec::leaf(Style::default(), MeasureFunc::Raw(|_, _| Size { width: 100.0, height: 50.0 })),
+            ],
+        );
+        let (root, nodes) = stretch.build(spec);
+
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[0], root);
+        assert_eq!(stretch.children(root).unwrap(), vec![nodes[1], nodes[3]]);
+        assert_eq!(stretch.children(nodes[1]).unwrap(), vec![nodes[2]]);
+        assert_eq!(*stretch.get_context(nodes[1]).unwrap(), 1);
+
+        stretch.compute_layout(root, Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(root).unwrap().size, Size { width: 100.0, height: 50.0 });
+        assert_eq!(stretch.layout(nodes[3]).unwrap().size.width, 100.0);
+    }
+
     #[test]
     fn add_child() {
         let mut stretch = Stretch::new();
@@ -151,6 +181,454 @@ mod node {
         assert_eq!(stretch.style(node).unwrap().display, Display::None);
     }
 
+    #[test]
+    fn set_style_of_shared_default() {
+        let mut stretch = Stretch::new();
+        let node1 = stretch.new_node(Style::default(), &[]).unwrap();
+        let node2 = stretch.new_node(Style::default(), &[]).unwrap();
+
+        stretch.set_style(node1, Style { display: Display::None, ..Style::default() }).unwrap();
+        assert_eq!(stretch.style(node1).unwrap().display, Display::None);
+        assert_eq!(stretch.style(node2).unwrap().display, Display::Flex);
+
+        stretch.remove(node2);
+        stretch.set_style(node1, Style::default()).unwrap();
+        let node3 = stretch.new_node(Style { flex_grow: 1.0, ..Style::default() }, &[]).unwrap();
+        assert_eq!(stretch.style(node1).unwrap().flex_grow, 0.0);
+        assert_eq!(stretch.style(node3).unwrap().flex_grow, 1.0);
+    }
+
+    #[test]
+    fn update_registered_style() {
+        let mut stretch = Stretch::new();
+        let width = |width| Style {
+            size: Size { width: Dimension::Points(width), height: Dimension::Auto },
+            ..Style::default()
+        };
+
+        let row = stretch.register_style(width(10.0));
+        let child1 = stretch.new_node_with_style_id(row, &[]).unwrap();
+        let child2 = stretch.new_node_with_style_id(row, &[]).unwrap();
+        let child3 = stretch.new_node(width(10.0), &[]).unwrap();
+        let node = stretch.new_node(Style::default(), &[child1, child2, child3]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.update_style(row, width(20.0)).unwrap();
+        assert!(stretch.dirty(child1).unwrap());
+        assert!(stretch.dirty(child2).unwrap());
+        assert!(!stretch.dirty(child3).unwrap());
+
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(child1).unwrap().size.width, 20.0);
+        assert_eq!(stretch.layout(child2).unwrap().size.width, 20.0);
+        assert_eq!(stretch.layout(child3).unwrap().size.width, 10.0);
+    }
+
+    #[test]
+    fn update_registered_style_after_remove() {
+        let mut stretch = Stretch::<u32>::default();
+        let style = stretch.register_style(Style::default());
+        let leaf = stretch
+            .new_leaf_with_style_id_and_context(
+                style,
+                MeasureFunc::Raw(|_, size| Size { width: *size as f32, height: 10.0 }),
+                10,
+            )
+            .unwrap();
+        let removed = stretch.new_node_with_style_id_and_context(style, &[], 1).unwrap();
+        let node = stretch.new_node_with_style_id_and_context(style, &[leaf], 2).unwrap();
+        assert_eq!(*stretch.get_context(leaf).unwrap(), 10);
+
+        // Removing a node moves the last node into its place, which has to stay a user of the style.
+        stretch.remove(removed);
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        stretch.update_style(style, Style { flex_grow: 1.0, ..Style::default() }).unwrap();
+        assert!(stretch.dirty(leaf).unwrap());
+        assert!(stretch.dirty(node).unwrap());
+        assert_eq!(*stretch.get_context(node).unwrap(), 2);
+
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(leaf).unwrap().size.width, 10.0);
+        assert_eq!(stretch.style(node).unwrap().flex_grow, 1.0);
+    }
+
+    #[test]
+    fn set_style_detaches_from_registered_style() {
+        let mut stretch = Stretch::new();
+        let style = stretch.register_style(Style::default());
+        let node1 = stretch.new_node_with_style_id(style, &[]).unwrap();
+        let node2 = stretch.new_node(Style::default(), &[]).unwrap();
+        stretch.set_style_id(node2, style).unwrap();
+
+        stretch.set_style(node1, Style { flex_grow: 1.0, ..Style::default() }).unwrap();
+        stretch.update_style(style, Style { flex_shrink: 0.0, ..Style::default() }).unwrap();
+        assert_eq!(stretch.style(node1).unwrap().flex_shrink, 1.0);
+        assert_eq!(stretch.style(node2).unwrap().flex_shrink, 0.0);
+
+        stretch.unregister_style(style).unwrap();
+        assert!(stretch.registered_style(style).is_err());
+        assert!(stretch.new_node_with_style_id(style, &[]).is_err());
+        assert_eq!(stretch.style(node2).unwrap().flex_shrink, 0.0);
+    }
+
+    #[test]
+    fn set_context() {
+        let mut stretch = Stretch::<u32>::default();
+
+        let node = stretch.new_node_with_context(Style::default(), &[], 1).unwrap();
+        assert_eq!(*stretch.get_context(node).unwrap(), 1);
+
+        *stretch.get_context_mut(node).unwrap() += 1;
+        assert_eq!(*stretch.get_context(node).unwrap(), 2);
+
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        stretch.set_context(node, 3).unwrap();
+        assert_eq!(*stretch.get_context(node).unwrap(), 3);
+        assert!(stretch.dirty(node).unwrap());
+    }
+
+    #[test]
+    fn compute_layouts() {
+        let mut stretch = Stretch::new();
+        let style = Style {
+            size: Size { width: Dimension::Percent(0.5), height: Dimension::Points(10.0) },
+            ..Default::default()
+        };
+        let node1 = stretch.new_node(style, &[]).unwrap();
+        let node2 = stretch.new_node(style, &[]).unwrap();
+        let invalid = Stretch::new().new_node(style, &[]).unwrap();
+
+        let sizes = [
+            Size { width: Number::Defined(100.0), height: Number::Undefined },
+            Size { width: Number::Defined(60.0), height: Number::Undefined },
+        ];
+        let results = stretch.compute_layouts(&[(node1, sizes[0]), (invalid, sizes[0]), (node2, sizes[1])]);
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert_eq!(stretch.layout(node1).unwrap().size.width, 50.0);
+        assert_eq!(stretch.layout(node2).unwrap().size.width, 30.0);
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut stretch = Stretch::<u32>::default();
+        let measure = |_: Size<Number>, width: &u32| Size { width: *width as f32, height: 10.0 };
+        let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 100).unwrap();
+        let node = stretch.new_node(Style::default(), &[leaf]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let snapshot = stretch.snapshot();
+        let child = stretch.new_node(Style::default(), &[]).unwrap();
+        stretch.add_child(node, child).unwrap();
+        stretch.set_style(leaf, Style { display: Display::None, ..Style::default() }).unwrap();
+
+        stretch.restore(snapshot, |_, _| MeasureFunc::Raw(measure));
+        assert!(stretch.layout(child).is_err());
+        assert_eq!(stretch.children(node).unwrap()[..], [leaf]);
+        assert_eq!(stretch.style(leaf).unwrap().display, Display::Flex);
+        assert_eq!(stretch.layout(node).unwrap().size.width, 100.0);
+        assert!(!stretch.dirty(node).unwrap());
+    }
+
+    #[test]
+    fn restore_in_other_instance() {
+        let measure = |_: Size<Number>, width: &u32| Size { width: *width as f32, height: 10.0 };
+        let mut restored = Stretch::<u32>::default();
+        // Restored from within a closure, so both instances and the snapshot are not all on the
+        // stack at once, which is tight without std.
+        let restore_from_other = |restored: &mut Stretch<u32>| {
+            let mut stretch = Stretch::<u32>::default();
+            let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 100).unwrap();
+            let node = stretch.new_node(Style::default(), &[leaf]).unwrap();
+            stretch.compute_layout(node, Size::undefined()).unwrap();
+            restored.restore(stretch.snapshot(), |_, _| MeasureFunc::Raw(measure));
+            (node, leaf)
+        };
+        let (node, leaf) = restore_from_other(&mut restored);
+
+        restored.set_context(leaf, 50).unwrap();
+        restored.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(restored.layout(node).unwrap().size.width, 50.0);
+    }
+
+    #[test]
+    fn tree() {
+        let mut stretch = Stretch::new();
+        let child0 = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Points(10.0), height: Dimension::Auto }, ..Style::default() },
+                &[],
+            )
+            .unwrap();
+        let child1 = stretch.new_node(Style { flex_grow: 1.0, ..Style::default() }, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: Rect { top: Dimension::Points(5.0), ..Default::default() },
+                    size: Size { width: Dimension::Percent(0.5), height: Dimension::Points(50.0) },
+                    ..Style::default()
+                },
+                &[child0, child1],
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size { width: Number::Defined(100.0), height: Number::Undefined }).unwrap();
+        stretch.mark_dirty(child1).unwrap();
+
+        assert_eq!(
+            stretch.tree(node).unwrap().to_string(),
+            "#2 column width=50% height=50px | size 50x50 at (0, 0) absolute (0, 0) dirty\n\
+             \x20 #0 width=10px | size 10x0 at (0, 5) absolute (0, 5)\n\
+             \x20 #1 grow=1 | size 50x45 at (0, 5) absolute (0, 5) dirty\n"
+        );
+        // Absolute locations stay relative to the root the layout was computed from.
+        assert_eq!(
+            stretch.tree(child1).unwrap().to_string(),
+            "#1 grow=1 | size 50x45 at (0, 5) absolute (0, 5) dirty\n"
+        );
+    }
+
+    #[test]
+    fn absolute_location() {
+        let mut stretch = Stretch::new();
+        let size = |width, height| Size { width: Dimension::Points(width), height: Dimension::Points(height) };
+        let leaf = stretch.new_node(Style { size: size(10.0, 10.0), ..Style::default() }, &[]).unwrap();
+        let child = stretch
+            .new_node(
+                Style {
+                    padding: Rect { start: Dimension::Points(5.0), top: Dimension::Points(7.0), ..Default::default() },
+                    ..Style::default()
+                },
+                &[leaf],
+            )
+            .unwrap();
+        let spacer = stretch.new_node(Style { size: size(20.0, 10.0), ..Style::default() }, &[]).unwrap();
+        let node = stretch.new_node(Style::default(), &[spacer, child]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        assert_eq!(stretch.layout(leaf).unwrap().location, Point { x: 5.0, y: 7.0 });
+        assert_eq!(stretch.layout(leaf).unwrap().absolute_location, Point { x: 25.0, y: 7.0 });
+        assert_eq!(stretch.layout(child).unwrap().absolute_location, Point { x: 20.0, y: 0.0 });
+    }
+
+    #[test]
+    fn rounding() {
+        let mut stretch = Stretch::new();
+        let grow = Style { flex_grow: 1.0, ..Style::default() };
+        let children: Vec<_> = (0..3).map(|_| stretch.new_node(grow, &[]).unwrap()).collect();
+        let node = stretch
+            .new_node(
+                Style {
+                    size: Size { width: Dimension::Points(100.0), height: Dimension::Points(10.0) },
+                    ..Style::default()
+                },
+                &children,
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let widths = |stretch: &Stretch| -> Vec<f32> {
+            children.iter().map(|child| stretch.layout(*child).unwrap().size.width).collect()
+        };
+        assert!(matches!(stretch.rounding(), Rounding::Round(scale_factor) if scale_factor == 1.0));
+        assert_eq!(widths(&stretch), vec![33.0, 34.0, 33.0]);
+        assert_eq!(stretch.layout(children[2]).unwrap().location.x, 67.0);
+
+        let unrounded = stretch.unrounded_layout(children[2]).unwrap();
+        assert!((unrounded.location.x - 200.0 / 3.0).abs() < 1e-4);
+        assert!((unrounded.absolute_location.x - 200.0 / 3.0).abs() < 1e-4);
+
+        stretch.set_rounding(Rounding::None).unwrap();
+        assert_eq!(
+            stretch.layout(children[1]).unwrap().location,
+            stretch.unrounded_layout(children[1]).unwrap().location
+        );
+        assert!((stretch.layout(children[1]).unwrap().size.width - 100.0 / 3.0).abs() < 1e-4);
+
+        stretch.set_rounding(Rounding::Round(3.0)).unwrap();
+        let location = stretch.layout(children[1]).unwrap().location.x;
+        assert_eq!(location, 100.0 / 3.0);
+
+        stretch.set_rounding(Rounding::Custom(f32::floor)).unwrap();
+        assert_eq!(stretch.layout(children[2]).unwrap().location.x, 66.0);
+        assert_eq!(widths(&stretch), vec![33.0, 33.0, 34.0]);
+
+        // The rounding stays in effect for later layouts.
+        stretch
+            .set_style(
+                node,
+                Style {
+                    size: Size { width: Dimension::Points(50.0), height: Dimension::Points(10.0) },
+                    ..Style::default()
+                },
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(widths(&stretch), vec![16.0, 17.0, 17.0]);
+    }
+
+    #[test]
+    fn invalid_scale_factor() {
+        let mut stretch = Stretch::new();
+        for scale_factor in [0.0, -2.0, f32::NAN, f32::INFINITY].iter() {
+            let error = stretch.set_rounding(Rounding::Round(*scale_factor)).unwrap_err();
+            assert!(matches!(error, stretch::Error::InvalidScaleFactor(_)));
+        }
+        assert!(matches!(stretch.rounding(), Rounding::Round(scale_factor) if scale_factor == 1.0));
+    }
+
+    #[test]
+    fn layout_changes() {
+        let mut stretch = Stretch::new();
+        let width = |width| Style {
+            size: Size { width: Dimension::Points(width), height: Dimension::Auto },
+            ..Style::default()
+        };
+        let first = stretch.new_node(width(50.0), &[]).unwrap();
+        let leaf = stretch.new_node(Style::default(), &[]).unwrap();
+        let second = stretch.new_node(Style { flex_grow: 1.0, ..Style::default() }, &[leaf]).unwrap();
+        let node = stretch.new_node(width(100.0), &[first, second]).unwrap();
+
+        let changes = stretch.compute_layout_changes(node, Size::undefined()).unwrap();
+        assert_eq!(changes.resized[..], [node, first, second]);
+        assert_eq!(changes.moved[..], [second]);
+        assert!(changes.hidden.is_empty() && changes.shown.is_empty());
+
+        assert!(stretch.compute_layout_changes(node, Size::undefined()).unwrap().is_empty());
+
+        stretch.set_style(first, width(60.0)).unwrap();
+        let changes = stretch.compute_layout_changes(node, Size::undefined()).unwrap();
+        assert_eq!(changes.resized[..], [first, second]);
+        assert_eq!(changes.moved[..], [second]);
+
+        stretch.set_style(second, Style { display: Display::None, ..Style::default() }).unwrap();
+        let changes = stretch.compute_layout_changes(node, Size::undefined()).unwrap();
+        assert_eq!(changes.hidden[..], [second, leaf]);
+        assert!(changes.resized.is_empty() && changes.moved.is_empty());
+
+        stretch.set_style(second, Style { flex_grow: 1.0, ..Style::default() }).unwrap();
+        let changes = stretch.compute_layout_changes(node, Size::undefined()).unwrap();
+        assert_eq!(changes.shown[..], [second, leaf]);
+        assert_eq!(changes.resized[..], [second]);
+        assert_eq!(changes.moved[..], [second]);
+    }
+
+    #[test]
+    fn resolved_edges() {
+        let mut stretch = Stretch::new();
+        let edges = |value| Rect { start: value, end: value, top: value, bottom: value };
+        let child = stretch
+            .new_node(
+                Style {
+                    margin: Rect { start: Dimension::Points(4.0), ..Default::default() },
+                    border: edges(Dimension::Points(1.0)),
+                    padding: edges(Dimension::Percent(0.1)),
+                    size: Size { width: Dimension::Points(50.0), height: Dimension::Points(50.0) },
+                    ..Style::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    padding: Rect { start: Dimension::Points(10.0), ..Default::default() },
+                    size: Size { width: Dimension::Points(110.0), height: Dimension::Points(100.0) },
+                    ..Style::default()
+                },
+                &[child],
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        // Percentages resolve against the width of the parent, including its padding.
+        let layout = stretch.layout(child).unwrap();
+        assert_eq!(layout.margin, Rect { start: 4.0, end: 0.0, top: 0.0, bottom: 0.0 });
+        assert_eq!(layout.border, Rect { start: 1.0, end: 1.0, top: 1.0, bottom: 1.0 });
+        assert_eq!(layout.padding, Rect { start: 11.0, end: 11.0, top: 11.0, bottom: 11.0 });
+        assert_eq!(layout.content_box(), Rect { start: 26.0, end: 52.0, top: 12.0, bottom: 38.0 });
+        assert_eq!(stretch.layout(node).unwrap().padding.start, 10.0);
+    }
+
+    #[test]
+    fn hit_test() {
+        let mut stretch = Stretch::new();
+        let size = |width, height| Size { width: Dimension::Points(width), height: Dimension::Points(height) };
+        let inner = stretch.new_node(Style { size: size(10.0, 10.0), ..Style::default() }, &[]).unwrap();
+        let child0 = stretch.new_node(Style { size: size(50.0, 50.0), ..Style::default() }, &[inner]).unwrap();
+        let overlay = stretch
+            .new_node(
+                Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        start: Dimension::Points(40.0),
+                        top: Dimension::Points(0.0),
+                        ..Default::default()
+                    },
+                    size: size(20.0, 20.0),
+                    ..Style::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let hidden = stretch
+            .new_node(
+                Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    size: size(100.0, 100.0),
+                    ..Style::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let node = stretch
+            .new_node(Style { size: size(100.0, 100.0), ..Style::default() }, &[child0, overlay, hidden])
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let hit = |root, x, y| stretch.hit_test(root, Point { x, y }).unwrap();
+        assert_eq!(hit(node, 5.0, 5.0), Some(inner));
+        assert_eq!(hit(node, 20.0, 20.0), Some(child0));
+        assert_eq!(hit(node, 45.0, 5.0), Some(overlay));
+        assert_eq!(hit(node, 80.0, 80.0), Some(node));
+        assert_eq!(hit(node, 150.0, 5.0), None);
+        assert_eq!(hit(child0, 5.0, 5.0), Some(inner));
+        assert_eq!(hit(inner, 5.0, 5.0), Some(inner));
+        // Points are relative to the root of the hit test, wherever it was laid out.
+        assert_eq!(hit(overlay, 5.0, 5.0), Some(overlay));
+        assert_eq!(hit(overlay, 25.0, 5.0), None);
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn relayout_shared_node() {
+        let mut stretch = Stretch::<f32>::default();
+        let measure = |_: Size<Number>, width: &f32| Size { width: *width, height: 10.0 };
+        let leaf = stretch.new_leaf_with_context(Style::default(), MeasureFunc::Raw(measure), 10.0).unwrap();
+        let fixed = Style {
+            flex_shrink: 0.0,
+            size: Size { width: Dimension::Points(50.0), height: Dimension::Points(50.0) },
+            ..Style::default()
+        };
+        let shared = stretch.new_node(fixed, &[leaf]).unwrap();
+        let offset =
+            Style { padding: Rect { start: Dimension::Points(30.0), ..Default::default() }, ..Style::default() };
+        let parent = stretch.new_node(Style::default(), &[shared]).unwrap();
+        let root0 = stretch.new_node(offset, &[parent]).unwrap();
+        let root1 = stretch.new_node(Style::default(), &[shared]).unwrap();
+        stretch.compute_layout(root0, Size::undefined()).unwrap();
+        stretch.compute_layout(root1, Size::undefined()).unwrap();
+
+        // The shared node is laid out again within `root1`, although its first parent is in `root0`.
+        stretch.set_context(leaf, 20.0).unwrap();
+        stretch.compute_layout(root1, Size::undefined()).unwrap();
+        assert_eq!(stretch.layout(leaf).unwrap().size.width, 20.0);
+        assert_eq!(stretch.layout(shared).unwrap().absolute_location, Point { x: 0.0, y: 0.0 });
+    }
+
     #[test]
     fn mark_dirty() {
         let mut stretch = Stretch::new();
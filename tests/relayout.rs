@@ -0,0 +1,540 @@
+#[cfg(test)]
+mod relayout {
+    use std::sync::atomic;
+
+    use stretch::geometry::*;
+    use stretch::node::{MeasureFunc, Node, Stretch};
+    use stretch::number::*;
+    use stretch::style::*;
+
+    /// Text leaves carry the width of their text as context, other nodes have none.
+    type Tree = Stretch<Option<f32>>;
+
+    fn layouts<T>(stretch: &Stretch<T>, nodes: &[Node]) -> Vec<(f32, f32, f32, f32)> {
+        nodes
+            .iter()
+            .map(|node| {
+                let layout = stretch.layout(*node).unwrap();
+                (layout.location.x, layout.location.y, layout.size.width, layout.size.height)
+            })
+            .collect()
+    }
+
+    /// A leaf measured like a single line of text `width` wide.
+    fn text(stretch: &mut Tree, style: Style, width: f32) -> Node {
+        let measure = MeasureFunc::Raw(|constraint, width: &Option<f32>| Size {
+            width: constraint.width.or_else(width.unwrap_or(0.0)),
+            height: constraint.height.or_else(10.0),
+        });
+        stretch.new_leaf_with_context(style, measure, Some(width)).unwrap()
+    }
+
+    /// A relayout boundary, a node whose size is fixed by its own style.
+    fn boundary(stretch: &mut Tree, children: &[Node]) -> Node {
+        stretch.new_node(boundary_style(), children).unwrap()
+    }
+
+    fn boundary_style() -> Style {
+        Style {
+            flex_shrink: 0.0,
+            size: Size { width: Dimension::Points(50.0), height: Dimension::Points(50.0) },
+            ..Default::default()
+        }
+    }
+
+    /// Asserts that the tree of `root` is laid out the same as a copy of it built and laid out from scratch.
+    fn assert_fresh(stretch: &Tree, root: Node, size: Size<Number>) {
+        fn copy(from: &Tree, to: &mut Tree, node: Node, nodes: &mut Vec<(Node, Node)>) -> Node {
+            let style = *from.style(node).unwrap();
+            let copy = match *from.get_context(node).unwrap() {
+                Some(width) => text(to, style, width),
+                None => {
+                    let children: Vec<_> =
+                        from.children(node).unwrap().into_iter().map(|child| copy(from, to, child, nodes)).collect();
+                    to.new_node(style, &children).unwrap()
+                }
+            };
+            nodes.push((node, copy));
+            copy
+        }
+
+        let mut fresh = Tree::default();
+        let mut nodes = Vec::new();
+        let fresh_root = copy(stretch, &mut fresh, root, &mut nodes);
+        fresh.compute_layout(fresh_root, size).unwrap();
+
+        let (nodes, fresh_nodes): (Vec<_>, Vec<_>) = nodes.into_iter().unzip();
+        assert_eq!(layouts(stretch, &nodes), layouts(&fresh, &fresh_nodes));
+    }
+
+    #[test]
+    fn identical_style_does_not_dirty() {
+        let mut stretch = Stretch::new();
+        let child = stretch.new_node(Style::default(), &[]).unwrap();
+        let node = stretch.new_node(Style::default(), &[child]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.set_style(child, Style::default()).unwrap();
+        assert!(!stretch.dirty(child).unwrap());
+        assert!(!stretch.dirty(node).unwrap());
+
+        stretch.set_style(child, Style { overflow: Overflow::Hidden, ..Default::default() }).unwrap();
+        assert!(!stretch.dirty(child).unwrap());
+        assert!(!stretch.dirty(node).unwrap());
+    }
+
+    #[test]
+    fn position_change_moves_child() {
+        let mut stretch = Stretch::new();
+        let style = Style {
+            size: Size { width: Dimension::Points(10.0), height: Dimension::Points(10.0) },
+            ..Default::default()
+        };
+        let child0 = stretch.new_node(style, &[]).unwrap();
+        let child1 = stretch.new_node(style, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Points(100.0), height: Dimension::Auto }, ..Default::default() },
+                &[child0, child1],
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let moved = Style {
+            position: Rect { start: Dimension::Percent(0.1), top: Dimension::Points(5.0), ..Default::default() },
+            ..style
+        };
+        stretch.set_style(child0, moved).unwrap();
+        assert!(stretch.dirty(child0).unwrap());
+        assert!(stretch.dirty(node).unwrap());
+        assert!(!stretch.dirty(child1).unwrap());
+
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(
+            layouts(&stretch, &[node, child0, child1]),
+            vec![(0.0, 0.0, 100.0, 10.0), (10.0, 5.0, 10.0, 10.0), (10.0, 0.0, 10.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn invalidation_stops_at_fixed_size_node() {
+        static SIBLING_MEASURES: atomic::AtomicU32 = atomic::AtomicU32::new(0);
+
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let boundary = boundary(&mut stretch, &[leaf]);
+        let sibling = stretch
+            .new_leaf(
+                Style::default(),
+                MeasureFunc::Raw(|constraint, _| {
+                    SIBLING_MEASURES.fetch_add(1, atomic::Ordering::Relaxed);
+                    Size { width: constraint.width.or_else(30.0), height: constraint.height.or_else(30.0) }
+                }),
+            )
+            .unwrap();
+        let node = stretch.new_node(Style::default(), &[boundary, sibling]).unwrap();
+        stretch.compute_layout(node, Size { width: Number::Defined(200.0), height: Number::Undefined }).unwrap();
+
+        let sibling_measures = SIBLING_MEASURES.load(atomic::Ordering::Relaxed);
+        stretch.set_context(leaf, Some(40.0)).unwrap();
+        assert!(stretch.dirty(node).unwrap());
+
+        stretch.compute_layout(node, Size { width: Number::Defined(200.0), height: Number::Undefined }).unwrap();
+        assert_eq!(SIBLING_MEASURES.load(atomic::Ordering::Relaxed), sibling_measures);
+        assert!(!stretch.dirty(node).unwrap());
+        assert!(!stretch.dirty(leaf).unwrap());
+
+        assert_eq!(
+            layouts(&stretch, &[node, boundary, leaf, sibling]),
+            vec![(0.0, 0.0, 80.0, 50.0), (0.0, 0.0, 50.0, 50.0), (0.0, 0.0, 40.0, 50.0), (50.0, 0.0, 30.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn relayout_starts_at_boundary() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let boundary = boundary(&mut stretch, &[leaf]);
+        let sibling = stretch.new_node(Style { flex_grow: 1.0, ..Default::default() }, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Percent(1.0), height: Dimension::Auto }, ..Default::default() },
+                &[sibling, boundary],
+            )
+            .unwrap();
+
+        let size = Size { width: Number::Defined(100.0), height: Number::Undefined };
+        stretch.compute_layout(node, size).unwrap();
+        assert_eq!(stretch.visited_nodes(), 4);
+
+        stretch.compute_layout(node, size).unwrap();
+        assert_eq!(stretch.visited_nodes(), 0);
+
+        stretch.set_context(leaf, Some(40.0)).unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        assert_eq!(stretch.visited_nodes(), 2);
+        assert_eq!(
+            layouts(&stretch, &[sibling, boundary, leaf]),
+            vec![(0.0, 0.0, 50.0, 50.0), (50.0, 0.0, 50.0, 50.0), (0.0, 0.0, 40.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn relayout_without_boundary_starts_at_root() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let child = stretch.new_node(Style::default(), &[leaf]).unwrap();
+        let sibling = text(&mut stretch, Style::default(), 30.0);
+        let node = stretch.new_node(Style::default(), &[child, sibling]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.set_context(leaf, Some(40.0)).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.visited_nodes(), 4);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn relayout_dirty_subtrees_under_separate_boundaries() {
+        let mut stretch = Tree::default();
+        let leaf0 = text(&mut stretch, Style::default(), 20.0);
+        let boundary0 = boundary(&mut stretch, &[leaf0]);
+        let leaf1 = text(&mut stretch, Style::default(), 20.0);
+        let boundary1 = boundary(&mut stretch, &[leaf1]);
+        let sibling = text(&mut stretch, Style::default(), 30.0);
+        let node = stretch.new_node(Style::default(), &[boundary0, sibling, boundary1]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.set_context(leaf0, Some(40.0)).unwrap();
+        stretch.set_context(leaf1, Some(10.0)).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.visited_nodes(), 4);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn relayout_after_boundary_loses_fixed_size() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let boundary = boundary(&mut stretch, &[leaf]);
+        let node = stretch.new_node(Style::default(), &[boundary]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let auto_width = Style { size: Size { width: Dimension::Auto, ..boundary_style().size }, ..boundary_style() };
+        stretch.set_style(boundary, auto_width).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        // The former boundary is now sized by its content, so its parent is laid out again.
+        stretch.set_context(leaf, Some(80.0)).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.visited_nodes(), 3);
+        assert_eq!(layouts(&stretch, &[node, boundary]), vec![(0.0, 0.0, 80.0, 50.0), (0.0, 0.0, 80.0, 50.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn style_change_of_boundary() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let boundary = boundary(&mut stretch, &[leaf]);
+        let sibling = stretch.new_node(Style { flex_grow: 1.0, ..Default::default() }, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Points(100.0), height: Dimension::Auto }, ..Default::default() },
+                &[boundary, sibling],
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let wider =
+            Style { size: Size { width: Dimension::Points(70.0), ..boundary_style().size }, ..boundary_style() };
+        stretch.set_style(boundary, wider).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(layouts(&stretch, &[boundary, sibling]), vec![(0.0, 0.0, 70.0, 50.0), (70.0, 0.0, 30.0, 50.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn change_within_baseline_aligned_sibling() {
+        let mut stretch = Tree::default();
+        let first = text(&mut stretch, Style::default(), 20.0);
+        // Fixed in size, but its baseline is that of its first child.
+        let aligned = boundary(&mut stretch, &[first]);
+        let sibling = text(&mut stretch, Style::default(), 30.0);
+        let node = stretch
+            .new_node(Style { align_items: AlignItems::Baseline, ..Default::default() }, &[aligned, sibling])
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        let taller =
+            Style { size: Size { width: Dimension::Auto, height: Dimension::Points(30.0) }, ..Default::default() };
+        stretch.set_style(first, taller).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(layouts(&stretch, &[aligned, sibling]), vec![(0.0, 0.0, 50.0, 50.0), (50.0, 20.0, 30.0, 10.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn move_child_across_boundaries() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let boundary0 = boundary(&mut stretch, &[leaf]);
+        let boundary1 = boundary(&mut stretch, &[]);
+        let hidden = stretch.new_node(Style { display: Display::None, ..boundary_style() }, &[]).unwrap();
+        let node = stretch.new_node(Style::default(), &[boundary0, boundary1, hidden]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.remove_child(boundary0, leaf).unwrap();
+        stretch.add_child(boundary1, leaf).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(layouts(&stretch, &[leaf]), vec![(0.0, 0.0, 20.0, 50.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+
+        stretch.remove_child(boundary1, leaf).unwrap();
+        stretch.add_child(hidden, leaf).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(layouts(&stretch, &[leaf]), vec![(0.0, 0.0, 0.0, 0.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn cached_layout_depends_on_parent_size() {
+        let mut stretch = Tree::default();
+        let leaf = stretch
+            .new_node(
+                Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    size: Size { width: Dimension::Points(31.3), height: Dimension::Points(24.7) },
+                    ..Default::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let child0 = stretch
+            .new_node(
+                Style {
+                    margin: Rect { start: Dimension::Points(45.5), ..Default::default() },
+                    size: Size { width: Dimension::Auto, height: Dimension::Percent(0.19) },
+                    ..Default::default()
+                },
+                &[leaf],
+            )
+            .unwrap();
+        let child1 =
+            stretch.new_node(Style { flex_direction: FlexDirection::Column, ..Default::default() }, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    size: Size { width: Dimension::Auto, height: Dimension::Percent(0.02) },
+                    max_size: Size { width: Dimension::Points(57.5), height: Dimension::Auto },
+                    ..Default::default()
+                },
+                &[child0, child1],
+            )
+            .unwrap();
+        let size = Size { width: Number::Defined(200.0), height: Number::Undefined };
+        stretch.compute_layout(node, size).unwrap();
+
+        // Percentage padding resolves against the width of the parent.
+        stretch
+            .set_style(
+                child0,
+                Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    align_items: AlignItems::Center,
+                    padding: Rect { top: Dimension::Percent(0.63), ..Default::default() },
+                    flex_grow: 2.0,
+                    size: Size { width: Dimension::Points(8.5), height: Dimension::Auto },
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        assert_fresh(&stretch, node, size);
+
+        stretch
+            .set_style(
+                child1,
+                Style {
+                    flex_direction: FlexDirection::Column,
+                    padding: Rect { top: Dimension::Points(45.5), ..Default::default() },
+                    size: Size { width: Dimension::Points(27.3), height: Dimension::Points(49.7) },
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        assert_eq!(stretch.layout(leaf).unwrap().location.y, 72.0);
+        assert_fresh(&stretch, node, size);
+    }
+
+    #[test]
+    fn show_hidden_subtree() {
+        let mut stretch = Tree::default();
+        let leaf = text(&mut stretch, Style::default(), 20.0);
+        let child = stretch.new_node(Style::default(), &[leaf]).unwrap();
+        let hidden = stretch
+            .new_node(
+                Style { display: Display::None, position_type: PositionType::Absolute, ..Default::default() },
+                &[child],
+            )
+            .unwrap();
+        let node = stretch.new_node(Style::default(), &[hidden]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        stretch.set_style(hidden, Style::default()).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(layouts(&stretch, &[child, leaf]), vec![(0.0, 0.0, 20.0, 10.0), (0.0, 0.0, 20.0, 10.0)]);
+        assert_fresh(&stretch, node, Size::undefined());
+    }
+
+    #[test]
+    fn measuring_baselines_keeps_layout_of_children() {
+        let mut stretch = Tree::default();
+        let leaf = text(
+            &mut stretch,
+            Style { size: Size { width: Dimension::Percent(0.25), height: Dimension::Auto }, ..Default::default() },
+            33.0,
+        );
+        let child = stretch
+            .new_node(
+                Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    flex_grow: 2.0,
+                    size: Size { width: Dimension::Percent(0.31), height: Dimension::Auto },
+                    ..Default::default()
+                },
+                &[leaf],
+            )
+            .unwrap();
+        let sibling = text(
+            &mut stretch,
+            Style {
+                margin: Rect { start: Dimension::Auto, ..Default::default() },
+                padding: Rect { top: Dimension::Percent(0.77), ..Default::default() },
+                size: Size { width: Dimension::Percent(0.94), height: Dimension::Auto },
+                ..Default::default()
+            },
+            57.0,
+        );
+        // Measuring this node lays out its children to find their baselines.
+        let aligned = stretch
+            .new_node(
+                Style {
+                    align_items: AlignItems::Baseline,
+                    flex_shrink: 0.0,
+                    size: Size { width: Dimension::Points(50.3), height: Dimension::Points(36.7) },
+                    ..Default::default()
+                },
+                &[child, sibling],
+            )
+            .unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    size: Size { width: Dimension::Auto, height: Dimension::Points(45.5) },
+                    ..Default::default()
+                },
+                &[aligned],
+            )
+            .unwrap();
+        let size = Size { width: Number::Defined(200.0), height: Number::Undefined };
+        stretch.compute_layout(node, size).unwrap();
+
+        stretch.set_context(leaf, Some(26.0)).unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        stretch.mark_dirty(node).unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        assert_fresh(&stretch, node, size);
+    }
+
+    #[test]
+    fn measuring_baselines_keeps_layout_of_descendants() {
+        let mut stretch = Tree::default();
+        let title = text(&mut stretch, Style::default(), 56.0);
+        let header = stretch
+            .new_node(
+                Style { border: Rect { top: Dimension::Points(11.0), ..Default::default() }, ..Default::default() },
+                &[title],
+            )
+            .unwrap();
+        let leaf = text(&mut stretch, Style::default(), 14.0);
+        let child = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Percent(0.38), height: Dimension::Auto }, ..Default::default() },
+                &[leaf],
+            )
+            .unwrap();
+        let aligned = stretch.new_node(Style::default(), &[child]).unwrap();
+        let body = stretch
+            .new_node(
+                Style { size: Size { width: Dimension::Auto, height: Dimension::Points(10.3) }, ..Default::default() },
+                &[aligned],
+            )
+            .unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    min_size: Size { width: Dimension::Auto, height: Dimension::Points(26.3) },
+                    ..Default::default()
+                },
+                &[header, body],
+            )
+            .unwrap();
+        let size = Size { width: Number::Defined(257.0), height: Number::Undefined };
+        stretch.compute_layout(node, size).unwrap();
+
+        // Measuring `body` measures `aligned`, which lays out its children to find their baselines.
+        let style = Style {
+            align_items: AlignItems::Baseline,
+            padding: Rect { start: Dimension::Percent(0.28), ..Default::default() },
+            ..Default::default()
+        };
+        stretch.set_style(aligned, style).unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        stretch
+            .set_style(
+                title,
+                Style { margin: Rect { top: Dimension::Points(13.8), ..Default::default() }, ..Default::default() },
+            )
+            .unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        assert_fresh(&stretch, node, size);
+    }
+
+    #[test]
+    fn relayout_rounds_subtree_at_unrounded_position() {
+        let mut stretch = Stretch::new();
+        let leaf = stretch.new_node(Style { flex_grow: 1.0, ..Default::default() }, &[]).unwrap();
+        let boundary = stretch
+            .new_node(
+                Style {
+                    flex_shrink: 0.0,
+                    size: Size { width: Dimension::Points(10.5), height: Dimension::Points(10.0) },
+                    ..Default::default()
+                },
+                &[leaf],
+            )
+            .unwrap();
+        let spacer = stretch
+            .new_node(
+                Style {
+                    flex_shrink: 0.0,
+                    size: Size { width: Dimension::Points(10.6), height: Dimension::Points(10.0) },
+                    ..Default::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let node = stretch.new_node(Style::default(), &[spacer, boundary]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        let expected = layouts(&stretch, &[boundary, leaf]);
+
+        stretch.mark_dirty(leaf).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+        assert_eq!(stretch.visited_nodes(), 2);
+        assert_eq!(layouts(&stretch, &[boundary, leaf]), expected);
+    }
+}
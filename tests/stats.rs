@@ -0,0 +1,57 @@
+#[cfg(feature = "stats")]
+mod stats {
+    use stretch::geometry::*;
+    use stretch::node::MeasureFunc;
+    use stretch::number::*;
+    use stretch::result::LayoutStats;
+    use stretch::style::*;
+    use stretch::Stretch;
+
+    #[test]
+    fn counters() {
+        let mut stretch = Stretch::new();
+        let text = MeasureFunc::Raw(|constraint, _| Size {
+            width: constraint.width.or_else(20.0),
+            height: constraint.height.or_else(10.0),
+        });
+        let leaf = stretch.new_leaf(Style { flex_grow: 1.0, ..Style::default() }, text).unwrap();
+        let child =
+            stretch.new_node(Style { flex_direction: FlexDirection::Column, ..Style::default() }, &[leaf]).unwrap();
+        let node = stretch.new_node(Style::default(), &[child]).unwrap();
+        let size = Size { width: Number::Defined(100.0), height: Number::Undefined };
+        stretch.compute_layout(node, size).unwrap();
+
+        let stats = stretch.stats();
+        let computations: u32 = [node, child, leaf].iter().map(|node| stretch.computations(*node).unwrap()).sum();
+        assert_eq!(stats.computations, computations as usize);
+        assert_eq!(stats.cache_hits + stats.cache_misses, stats.computations);
+        assert_eq!(stretch.computations(node).unwrap(), 1);
+        assert_eq!(stats.max_depth, 3);
+        assert!(stats.measure_calls > 0);
+
+        // Nothing is computed again while the layout is valid.
+        stretch.compute_layout(node, size).unwrap();
+        assert_eq!(stretch.stats(), LayoutStats::default());
+        assert_eq!(stretch.computations(leaf).unwrap(), 0);
+
+        stretch.mark_dirty(leaf).unwrap();
+        stretch.compute_layout(node, size).unwrap();
+        let relayout = stretch.stats();
+        assert_eq!(relayout.measure_calls, 0);
+        assert_eq!(relayout.measure_cache_hits, stats.measure_calls);
+    }
+
+    #[test]
+    fn known_sizes_are_not_measure_calls() {
+        let mut stretch = Stretch::new();
+        let pending =
+            stretch.new_leaf(Style::default(), MeasureFunc::Pending(Size { width: 10.0, height: 10.0 })).unwrap();
+        let image =
+            stretch.new_leaf(Style::default(), MeasureFunc::Intrinsic(Size { width: 20.0, height: 20.0 })).unwrap();
+        let node = stretch.new_node(Style::default(), &[pending, image]).unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        assert_eq!(stretch.layout(node).unwrap().size, Size { width: 30.0, height: 20.0 });
+        assert_eq!(stretch.stats().measure_calls, 0);
+    }
+}
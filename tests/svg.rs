@@ -0,0 +1,77 @@
+#[cfg(feature = "svg")]
+mod svg {
+    use stretch::geometry::*;
+    use stretch::style::*;
+    use stretch::Stretch;
+
+    #[test]
+    fn render() {
+        let mut stretch = Stretch::new();
+        let child0 = stretch
+            .new_node(
+                Style {
+                    margin: Rect { start: Dimension::Points(5.0), ..Default::default() },
+                    size: Size { width: Dimension::Points(20.0), height: Dimension::Points(10.0) },
+                    ..Default::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let child1 = stretch
+            .new_node(
+                Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        end: Dimension::Points(0.0),
+                        bottom: Dimension::Points(0.0),
+                        ..Default::default()
+                    },
+                    size: Size { width: Dimension::Points(10.0), height: Dimension::Points(10.0) },
+                    ..Default::default()
+                },
+                &[],
+            )
+            .unwrap();
+        let hidden = stretch.new_node(Style { display: Display::None, ..Default::default() }, &[]).unwrap();
+        let node = stretch
+            .new_node(
+                Style {
+                    padding: Rect { top: Dimension::Percent(0.1), ..Default::default() },
+                    border: Rect {
+                        start: Dimension::Points(1.0),
+                        end: Dimension::Points(1.0),
+                        top: Dimension::Points(1.0),
+                        bottom: Dimension::Points(1.0),
+                    },
+                    size: Size { width: Dimension::Points(50.0), height: Dimension::Points(40.0) },
+                    ..Default::default()
+                },
+                &[child0, child1, hidden],
+            )
+            .unwrap();
+        stretch.compute_layout(node, Size::undefined()).unwrap();
+
+        assert_eq!(
+            stretch.to_svg(node).unwrap(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="40" viewBox="0 0 50 40">
+<rect x="0" y="0" width="50" height="40" fill="#f9cc9d"/>
+<rect x="0" y="0" width="50" height="40" fill="#fddd9b"/>
+<rect x="1" y="1" width="48" height="38" fill="#c3d08b"/>
+<rect x="1" y="1" width="48" height="38" fill="#8cb6c0"/>
+<text x="1" y="1" font-family="monospace" font-size="10" dominant-baseline="hanging">#3</text>
+<rect x="1" y="1" width="25" height="10" fill="#f9cc9d"/>
+<rect x="6" y="1" width="20" height="10" fill="#fddd9b"/>
+<rect x="6" y="1" width="20" height="10" fill="#c3d08b"/>
+<rect x="6" y="1" width="20" height="10" fill="#8cb6c0"/>
+<text x="6" y="1" font-family="monospace" font-size="10" dominant-baseline="hanging">#0</text>
+<rect x="39" y="29" width="10" height="10" fill="#f9cc9d"/>
+<rect x="39" y="29" width="10" height="10" fill="#fddd9b"/>
+<rect x="39" y="29" width="10" height="10" fill="#c3d08b"/>
+<rect x="39" y="29" width="10" height="10" fill="#8cb6c0"/>
+<rect x="39" y="29" width="10" height="10" fill="none" stroke="black" stroke-dasharray="4 2"/>
+<text x="39" y="29" font-family="monospace" font-size="10" dominant-baseline="hanging">#1</text>
+</svg>
+"##
+        );
+    }
+}